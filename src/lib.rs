@@ -1,8 +1,15 @@
-use std::fmt::Debug;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::Add;
+use std::str::FromStr;
 
 /// A graph structure with support for appending, traversing and removing nodes.
 pub struct Graph<D, K: PartialEq + Clone + Debug> {
     nodes: Vec<Node<D, K>>,
+    /// The largest amount of children a node may get by appending.
+    max_children: Option<usize>,
 }
 impl<D, K> Graph<D, K>
 where
@@ -10,7 +17,22 @@ where
 {
     /// Returns a empty graph.
     pub fn new() -> Self {
-        Graph { nodes: vec![] }
+        Graph {
+            nodes: vec![],
+            max_children: None,
+        }
+    }
+    /// Returns a graph holding only the given root. Returns an error if the root has a fathers key.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The first node of the graph.
+    ///
+    pub fn try_new_rooted(root: Node<D, K>) -> Result<Self, AppendError<K>> {
+        if root.father_key.is_some() {
+            return Err(AppendError::RootWithFather(root.key));
+        }
+        Ok(Graph::from_nodes(vec![root]))
     }
     /// Appends a node to graph. The first node shouldn't have a fathers key. All others need one. Will panic if the first node has a fathers key, if one except the first has none or father is not found.
     /// # Arguments
@@ -18,37 +40,116 @@ where
     /// * `node` - The node to append.
     ///
     pub fn append_node(&mut self, node: Node<D, K>) {
-        let mut added_key = false;
-        if !self.nodes.is_empty() {
-            for current_node in self.nodes.iter_mut() {
-                if let Some(father_key) = &node.father_key {
-                    if &current_node.key == father_key {
-                        current_node.children.push(node.key.clone());
-                        added_key = true;
-                        break;
-                    }
-                }
-            }
-        } else if node.father_key.is_none() && !self.nodes.is_empty() {
-            panic!("Every other node except the first needs a father key.");
-        } else if node.father_key.is_some() && self.nodes.is_empty() {
-            panic!("First node cant have a fathers key.");
+        if let Err(error) = self.try_append_node(node) {
+            panic!("{}", error);
         }
-
-        match (self.nodes.is_empty(), added_key) {
-            (true, _) => {}
-            (_, true) => {}
-            (false, false) => {
-                panic!("Needs father and no father found");
+    }
+    /// Appends a node to graph like `append_node`, but returns an error instead of panicking.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to append.
+    ///
+    pub fn try_append_node(&mut self, node: Node<D, K>) -> Result<(), AppendError<K>> {
+        self.can_append(&node)?;
+        if let Some(father_key) = &node.father_key {
+            if let Some(father) = self
+                .nodes
+                .iter_mut()
+                .find(|current_node| &current_node.key == father_key)
+            {
+                father.children.push(node.key.clone());
             }
         }
         self.nodes.push(node);
+        Ok(())
+    }
+    /// Checks if the node could be appended without changing the graph. Returns the error `try_append_node` would return otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to check.
+    ///
+    pub fn can_append(&self, node: &Node<D, K>) -> Result<(), AppendError<K>> {
+        match (&node.father_key, self.nodes.is_empty()) {
+            (None, true) => Ok(()),
+            (Some(_), true) => Err(AppendError::RootWithFather(node.key.clone())),
+            (None, false) => Err(AppendError::MissingFather(node.key.clone())),
+            (Some(father_key), false) => self.check_father(father_key),
+        }
+    }
+    /// Limits the amount of children a node may get by appending. Appending a child to a node which already has that many children fails with `AppendError::TooManyChildren`. Existing children are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The largest amount of children of a node.
+    ///
+    pub fn set_max_children(&mut self, max: usize) {
+        self.max_children = Some(max);
+    }
+    /// Appends a node to graph like `append_node` and returns a reference to the appended node. Panics under the same conditions.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to append.
+    ///
+    pub fn append_node_ref(&mut self, node: Node<D, K>) -> &Node<D, K> {
+        self.append_node(node);
+        &self.nodes[self.nodes.len() - 1]
+    }
+    /// Appends a node as child of given father at the given position of the fathers children. The fathers key of the node is set to the given father. An index past the end appends the child as last one.
+    ///
+    /// # Arguments
+    ///
+    /// * `father` - The key of the father of the node.
+    /// * `node` - The node to append.
+    /// * `index` - The position of the node among the children of the father.
+    ///
+    pub fn insert_child_at(
+        &mut self,
+        father: &K,
+        mut node: Node<D, K>,
+        index: usize,
+    ) -> Result<(), AppendError<K>> {
+        self.check_father(father)?;
+        let father_node = self
+            .nodes
+            .iter_mut()
+            .find(|current_node| &current_node.key == father)
+            .ok_or_else(|| AppendError::FatherNotFound(father.clone()))?;
+        let index = index.min(father_node.children.len());
+        father_node.children.insert(index, node.key.clone());
+        node.father_key = Some(father.clone());
+        self.nodes.push(node);
+        Ok(())
+    }
+    /// Appends the data as new last child of given father under a generated key which isn't used yet. Returns the generated key.
+    ///
+    /// # Arguments
+    ///
+    /// * `father` - The key of the father of the new node.
+    /// * `data` - The data of the new node.
+    ///
+    pub fn append_data_under(&mut self, father: &K, data: D) -> Result<K, AppendError<K>>
+    where
+        K: From<usize>,
+    {
+        let mut index = self.nodes.len();
+        let key = loop {
+            let key = K::from(index);
+            if find_node_with_key(&self.nodes, &key).is_none() {
+                break key;
+            }
+            index += 1;
+        };
+        self.insert_child_at(father, Node::new(data, key.clone(), None), usize::MAX)?;
+        Ok(key)
     }
-    /// Travels the graph with given path and returns a node if one is found.
+    /// Travels the graph with given path and returns a node if one is found. The route starts below the root, so an empty route returns the root and a route beginning with the roots key returns None.
     ///
     /// # Arguments
     ///
-    /// * `route` - A slice of keys. Will return the last key of the route if found.
+    /// * `route` - A slice of child keys descending from the root. Will return the last key of the route if found. Returns None if a key of the route is not a child of the previous node or its node doesn't exist.
     ///
     /// # Examples
     ///
@@ -70,12 +171,11 @@ where
     /// assert_eq!(node.unwrap().data,"Ok, dann einen Sitzlatz am Gang. Bis dann!");
     /// ```
     pub fn travel_to_node(&self, route: &[K]) -> Option<&Node<D, K>> {
-        let mut start_node = None;
+        let mut start_node = self.root();
 
-        for node in self.nodes.iter() {
-            if node.father_key.is_none() {
-                start_node = Some(node);
-                break;
+        if let (Some(root), Some(first_key)) = (start_node, route.first()) {
+            if &root.key == first_key {
+                return None;
             }
         }
 
@@ -87,8 +187,6 @@ where
                     if let Some(new_start_node) = find_node_with_key(&self.nodes, key) {
                         start_node = Some(new_start_node);
                         found_node = true;
-                    } else {
-                        println!("Couldnt set node to current");
                     }
                 }
                 if !found_node {
@@ -98,6 +196,17 @@ where
         }
         start_node
     }
+    /// Travels the graph with given path like `travel_to_node` and returns the found node mutably.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - A slice of child keys descending from the root. Will return the last key of the route if found.
+    ///
+    pub fn travel_to_node_mut(&mut self, route: &[K]) -> Option<&mut Node<D, K>> {
+        let key = self.travel_to_node(route)?.key.clone();
+        let index = find_position_with_key(&self.nodes, &key)?;
+        self.nodes.get_mut(index)
+    }
     /// Returns the amount of nodes in the graph.
     pub fn len(&self) -> usize {
         self.nodes.len()
@@ -138,136 +247,4855 @@ where
             delete_node(&mut self.nodes, &node);
         }
     }
-}
-/// Deletes given node from given Vector.
-///
-/// # Arguments
-///
-/// * `key` - Key of the node to delete
-/// * `nodes` - The vector where the node should be deleted from.
-///
-fn delete_node<'a, D, K: PartialEq + Clone + Debug>(nodes: &'a mut Vec<Node<D, K>>, key: &'a K) {
-    if let Some(index) = nodes.iter().position(|x| &x.key == key) {
-        nodes.remove(index);
+    /// Returns all nodes whose distance from the root equals the given depth. The root has depth 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - The depth of the nodes to return.
+    ///
+    pub fn nodes_at_depth(&self, depth: usize) -> Vec<&Node<D, K>> {
+        let mut current_level: Vec<&Node<D, K>> = self.root().into_iter().collect();
+        for _ in 0..depth {
+            current_level = current_level
+                .iter()
+                .flat_map(|node| node.children.iter())
+                .filter_map(|child| find_node_with_key(&self.nodes, child))
+                .collect();
+            if current_level.is_empty() {
+                break;
+            }
+        }
+        current_level
     }
-}
-/// Returns all attached keys of given key.
-///
-/// # Arguments
-///
-/// * `key` - Key which childs should be found
-///
-fn find_all_child_nodes<'a, D, K: PartialEq + Clone + Debug>(
-    nodes: &'a Vec<Node<D, K>>,
-    key: &'a K,
-) -> Vec<K> {
-    let mut all_nodes = vec![];
-    if let Some(node_to_delete) = find_node_with_key(nodes, key) {
-        for child in node_to_delete.children.iter() {
-            let mut found_childs = find_all_child_nodes(nodes, child);
-
-            all_nodes.append(&mut found_childs);
+    /// Returns true if both graphs hold the same keys with the same father and children relationships. The data of the nodes is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The graph to compare with.
+    ///
+    pub fn structurally_eq<D2>(&self, other: &Graph<D2, K>) -> bool {
+        self.len() == other.len()
+            && self.nodes.iter().all(|node| {
+                find_node_with_key(&other.nodes, &node.key).is_some_and(|other_node| {
+                    node.father_key == other_node.father_key && node.children == other_node.children
+                })
+            })
+    }
+    /// Returns all nodes without children.
+    pub fn leaves(&self) -> Vec<&Node<D, K>> {
+        self.nodes
+            .iter()
+            .filter(|node| node.children.is_empty())
+            .collect()
+    }
+    /// Returns the data of all nodes without children.
+    pub fn leaf_data(&self) -> Vec<&D> {
+        self.leaves().into_iter().map(|node| &node.data).collect()
+    }
+    /// Returns an iterator over all nodes reachable from the root in the given order. The iterator knows its exact length, so collecting it allocates only once.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The order in which the nodes are visited.
+    ///
+    pub fn iter_order(&self, order: TraversalOrder) -> impl ExactSizeIterator<Item = &Node<D, K>> {
+        let nodes = match (self.root(), order) {
+            (None, _) => vec![],
+            (Some(root), TraversalOrder::PreOrder) => self.pre_order_from(root),
+            (Some(root), TraversalOrder::PostOrder) => self.post_order_from(root),
+            (Some(root), TraversalOrder::LevelOrder) => self.level_order_from(root),
+        };
+        nodes.into_iter()
+    }
+    /// Removes the node with given key and all attached nodes and puts the replacement in its place. The root of the replacement takes the position of the removed node in its fathers children. Returns the removed nodes as their own graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to replace.
+    /// * `replacement` - The graph to put in place of the removed nodes.
+    ///
+    pub fn replace_subtree(
+        &mut self,
+        key: &K,
+        replacement: Graph<D, K>,
+    ) -> Result<Graph<D, K>, ReplaceError<K>> {
+        let node = find_node_with_key(&self.nodes, key)
+            .ok_or_else(|| ReplaceError::KeyNotFound(key.clone()))?;
+        let replacement_root_key = replacement
+            .root()
+            .map(|root| root.key.clone())
+            .ok_or(ReplaceError::EmptyReplacement)?;
+        let father_key = node.father_key.clone();
+        let subtree_keys: Vec<K> = self
+            .pre_order_from(node)
+            .into_iter()
+            .map(|node| node.key.clone())
+            .collect();
+        for replacement_node in replacement.nodes.iter() {
+            let collides = self
+                .nodes
+                .iter()
+                .any(|node| node.key == replacement_node.key && !subtree_keys.contains(&node.key));
+            if collides {
+                return Err(ReplaceError::KeyCollision(replacement_node.key.clone()));
+            }
+        }
 
-            all_nodes.push(child.clone());
+        let (mut removed, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .partition(|node| subtree_keys.contains(&node.key));
+        self.nodes = kept;
+        if let Some(removed_root) = removed.iter_mut().find(|node| &node.key == key) {
+            removed_root.father_key = None;
+        }
+        if let Some(father_key) = &father_key {
+            if let Some(father) = self.nodes.iter_mut().find(|node| &node.key == father_key) {
+                for child in father.children.iter_mut() {
+                    if child == key {
+                        *child = replacement_root_key.clone();
+                    }
+                }
+            }
+        }
+        for mut replacement_node in replacement.nodes {
+            if replacement_node.key == replacement_root_key {
+                replacement_node.father_key = father_key.clone();
+            }
+            self.nodes.push(replacement_node);
         }
+        Ok(Graph::from_nodes(removed))
     }
-    all_nodes
-}
-fn find_node_with_key<'a, D, K: PartialEq + Clone + Debug>(
-    nodes: &'a [Node<D, K>],
-    key: &K,
-) -> Option<&'a Node<D, K>> {
-    for node in nodes.iter() {
-        if &node.key == key {
-            return Some(node);
+    /// Returns the keys on the path from one node to another, going up to their lowest common ancestor and down again. Returns None if one of the keys is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The key of the node the path starts at.
+    /// * `b` - The key of the node the path ends at.
+    ///
+    pub fn path_between(&self, a: &K, b: &K) -> Option<Vec<K>> {
+        let path_a = self.path_to(a)?;
+        let path_b = self.path_to(b)?;
+        let shared = shared_prefix_len(&path_a, &path_b);
+        if shared == 0 {
+            return None;
         }
+        let mut path: Vec<K> = path_a[shared - 1..].iter().rev().cloned().collect();
+        path.extend(path_b[shared..].iter().cloned());
+        Some(path)
     }
-    None
-}
-/// A Node for the graph structure.
-pub struct Node<D, K: PartialEq + Clone + Debug> {
-    /// The data the node holds.
-    pub data: D,
-    /// All the keys of the children nodes.
-    children: Vec<K>,
-    /// The key of the father of the node.
-    father_key: Option<K>,
-    /// Key
-    key: K,
-}
-impl<D, K> Node<D, K>
-where
-    K: PartialEq + Clone + Debug,
-{
-    /// Returns a new Node without children. Fathers key has to be empty only for the first node of the graph. All other nodes need a valid fathers key.
-    pub fn new<T: Into<Option<K>>>(data: D, key: K, father_key: T) -> Self {
-        Node {
-            data,
-            key,
-            father_key: father_key.into(),
-            children: vec![],
+    /// Returns the amount of nodes matching the predicate.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Returns true for every node that should be counted.
+    ///
+    pub fn count_matching<P: FnMut(&Node<D, K>) -> bool>(&self, mut predicate: P) -> usize {
+        self.nodes.iter().filter(|node| predicate(node)).count()
+    }
+    /// Returns a copy of the graph with the function applied to every key. The function has to map different keys to different keys, otherwise the resulting graph is broken.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Returns the new key for given key.
+    ///
+    pub fn map_keys<K2, F>(&self, f: F) -> Graph<D, K2>
+    where
+        F: Fn(&K) -> K2,
+        K2: PartialEq + Clone + Debug,
+        D: Clone,
+    {
+        Graph::from_nodes(
+            self.nodes
+                .iter()
+                .map(|node| Node {
+                    data: node.data.clone(),
+                    children: node.children.iter().map(&f).collect(),
+                    father_key: node.father_key.as_ref().map(&f),
+                    key: f(&node.key),
+                    tags: node.tags.clone(),
+                    terminal: node.terminal,
+                })
+                .collect(),
+        )
+    }
+    /// Returns a copy of the graph with the function applied to the data of every node.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Returns the new data for given data.
+    ///
+    pub fn map_data<D2, F>(&self, f: F) -> Graph<D2, K>
+    where
+        F: Fn(&D) -> D2,
+    {
+        Graph::from_nodes(
+            self.nodes
+                .iter()
+                .map(|node| Node {
+                    data: f(&node.data),
+                    children: node.children.clone(),
+                    father_key: node.father_key.clone(),
+                    key: node.key.clone(),
+                    tags: node.tags.clone(),
+                    terminal: node.terminal,
+                })
+                .collect(),
+        )
+    }
+    /// Returns an overview of the size and shape of the graph.
+    pub fn stats(&self) -> GraphStats {
+        let is_tree = self.is_tree();
+        let max_depth = self
+            .root()
+            .filter(|_| is_tree)
+            .map(|root| self.level_order_with_depth_from(root))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, depth)| depth)
+            .max()
+            .unwrap_or(0);
+        let mut stats = GraphStats {
+            node_count: self.nodes.len(),
+            leaf_count: 0,
+            max_depth,
+            max_branching_factor: 0,
+            is_tree,
+        };
+        for node in self.nodes.iter() {
+            if node.children.is_empty() {
+                stats.leaf_count += 1;
+            }
+            stats.max_branching_factor = stats.max_branching_factor.max(node.children.len());
         }
+        stats
     }
-    /// Returns true if the node holds a childs key of given type.
-    fn has_child(&self, key: &K) -> bool {
-        for child_key in self.children.iter() {
-            if child_key == key {
-                return true;
+    /// Returns all nodes with exactly the given amount of children.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The amount of children the nodes need to have.
+    ///
+    pub fn nodes_with_child_count(&self, count: usize) -> Vec<&Node<D, K>> {
+        self.nodes
+            .iter()
+            .filter(|node| node.children.len() == count)
+            .collect()
+    }
+    /// Returns the keys from the root to the node with given key together with the summed weight of the edges on the way. As every node has exactly one path from the root, this is also the cheapest one.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to reach.
+    /// * `weight` - Returns the weight of the edge between given father and child key.
+    ///
+    pub fn cheapest_path_to<W, F>(&self, key: &K, weight: F) -> Option<(Vec<K>, W)>
+    where
+        F: Fn(&K, &K) -> W,
+        W: Add<Output = W> + Default,
+    {
+        let path = self.path_to(key)?;
+        let cost = path
+            .windows(2)
+            .fold(W::default(), |cost, edge| cost + weight(&edge[0], &edge[1]));
+        Some((path, cost))
+    }
+    /// Returns a copy of the graph with keys replaced by sequential indices in pre-order, together with the original keys at those indices. Nodes which can't be reached from the root get the indices after all reachable nodes.
+    pub fn to_indexed(&self) -> (Graph<D, usize>, Vec<K>)
+    where
+        D: Clone,
+    {
+        let mut order = self
+            .root()
+            .map(|root| self.pre_order_from(root))
+            .unwrap_or_default();
+        for node in self.nodes.iter() {
+            if !order.iter().any(|ordered| ordered.key == node.key) {
+                order.push(node);
             }
         }
-        false
+        let keys: Vec<K> = order.iter().map(|node| node.key.clone()).collect();
+        let index_of = |key: &K| keys.iter().position(|other| other == key);
+        let nodes = order
+            .iter()
+            .enumerate()
+            .map(|(index, node)| Node {
+                data: node.data.clone(),
+                children: node.children.iter().filter_map(index_of).collect(),
+                father_key: node.father_key.as_ref().and_then(index_of),
+                key: index,
+                tags: node.tags.clone(),
+                terminal: node.terminal,
+            })
+            .collect();
+        (Graph::from_nodes(nodes), keys)
     }
-}
-impl<D, K> Default for Graph<D, K>
-where
-    K: PartialEq + Clone + Debug,
-{
-    fn default() -> Self {
-        Graph::new()
+    /// Returns true if the graph has exactly one root, every node is reachable from it exactly once and every child points back to its father. Returns false for an empty graph.
+    pub fn is_tree(&self) -> bool {
+        let roots = self.nodes.iter().filter(|node| node.father_key.is_none());
+        let root = match (roots.count(), self.root()) {
+            (1, Some(root)) => root,
+            _ => return false,
+        };
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![&root.key];
+        while let Some(key) = stack.pop() {
+            let index = match find_position_with_key(&self.nodes, key) {
+                Some(index) => index,
+                None => return false,
+            };
+            if visited[index] {
+                return false;
+            }
+            visited[index] = true;
+            let node = &self.nodes[index];
+            for child in node.children.iter() {
+                if let Some(child_node) = find_node_with_key(&self.nodes, child) {
+                    if child_node.father_key.as_ref() != Some(&node.key) {
+                        return false;
+                    }
+                }
+                stack.push(child);
+            }
+        }
+        visited.into_iter().all(|visited| visited)
     }
-}
-#[cfg(test)]
-mod tests {
-    use crate::{Graph, Node};
-
-    #[test]
-    fn it_works() {
-        let first_node = Node::new(
-            "Hallo, willst du etwas Essen gehen, oder einen Sitzplatz buchen?",
-            "Start",
-            None,
-        );
-        let second_node = Node::new(
-            "Ok, was willst du essen? Pizza oder Pasta?",
-            "Essen",
-            "Start",
-        );
-        let third_node = Node::new(
-            "Ok, willst du am Fenster oder am Gang sitzen?",
-            "Sitzplatz",
-            "Start",
-        );
-        let fourth_node = Node::new(
-            "Ok, dann einen Sitzlatz am Gang. Bis dann!",
-            "Gang",
-            "Sitzplatz",
-        );
-
-        let nodes = vec![first_node, second_node, third_node, fourth_node];
-        let mut graph = Graph::new();
-
-        for node in nodes.into_iter() {
-            graph.append_node(node);
+    /// Returns the node with given key and all attached nodes, every father before its children. Returns an empty vector if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to start at.
+    ///
+    pub fn reachable_from(&self, key: &K) -> Vec<&Node<D, K>> {
+        find_node_with_key(&self.nodes, key)
+            .map(|node| self.pre_order_from(node))
+            .unwrap_or_default()
+    }
+    /// Removes every node deeper than the given depth. The nodes at the given depth lose their children.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_depth` - The depth of the deepest nodes to keep. The root has depth 0.
+    ///
+    pub fn truncate_depth(&mut self, max_depth: usize) {
+        let mut keys_to_remove = vec![];
+        let mut keys_to_cut = vec![];
+        if let Some(root) = self.root() {
+            for (node, depth) in self.level_order_with_depth_from(root) {
+                if depth > max_depth {
+                    keys_to_remove.push(node.key.clone());
+                } else if depth == max_depth {
+                    keys_to_cut.push(node.key.clone());
+                }
+            }
         }
-        assert_eq!(graph.len(), 4);
-        let node = graph.travel_to_node(&["Sitzplatz", "Gang"]);
-        assert_eq!(
-            node.unwrap().data,
-            "Ok, dann einen Sitzlatz am Gang. Bis dann!"
-        );
-
-        graph.remove_node_with_childs("Sitzplatz");
-
-        assert_eq!(graph.len(), 2);
+        self.nodes
+            .retain(|node| !keys_to_remove.contains(&node.key));
+        for node in self.nodes.iter_mut() {
+            if keys_to_cut.contains(&node.key) {
+                node.children.clear();
+            }
+        }
+    }
+    /// Merges sibling nodes without children which hold equal data into the first of them.
+    pub fn dedup_leaves(&mut self)
+    where
+        D: PartialEq,
+    {
+        let mut duplicate_keys = vec![];
+        for node in self.nodes.iter() {
+            let mut kept_leaves: Vec<&Node<D, K>> = vec![];
+            for child in self.child_nodes(node) {
+                if !child.children.is_empty() {
+                    continue;
+                }
+                if kept_leaves.iter().any(|kept| kept.data == child.data) {
+                    duplicate_keys.push(child.key.clone());
+                } else {
+                    kept_leaves.push(child);
+                }
+            }
+        }
+        self.nodes
+            .retain(|node| !duplicate_keys.contains(&node.key));
+        for node in self.nodes.iter_mut() {
+            node.children
+                .retain(|child| !duplicate_keys.contains(child));
+        }
+    }
+    /// Returns the index of the node with given key in the order the nodes were appended.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to find.
+    ///
+    pub fn position_of(&self, key: &K) -> Option<usize> {
+        find_position_with_key(&self.nodes, key)
+    }
+    /// Returns the graph as nested JSON starting at the root. Every node is an object holding its key, its data and the objects of its children. Returns `null` for an empty graph.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use graph::{Graph,Node};
+    /// let mut graph = Graph::new();
+    /// graph.append_node(Node::new("Hallo","Start",None));
+    /// graph.append_node(Node::new("Essen?","Essen",Some("Start")));
+    ///
+    /// assert_eq!(
+    ///     graph.to_nested_json(),
+    ///     r#"{"key":"Start","data":"Hallo","children":[{"key":"Essen","data":"Essen?","children":[]}]}"#
+    /// );
+    /// ```
+    pub fn to_nested_json(&self) -> String
+    where
+        D: Display,
+        K: Display,
+    {
+        let mut json = String::new();
+        match self.root() {
+            Some(root) => self.write_nested_json(root, &mut json, &mut self.visited_from(root)),
+            None => json.push_str("null"),
+        }
+        json
+    }
+    /// Returns a graph parsed from nested JSON as written by `to_nested_json`. Every node object needs a `key` and a `data` string and may hold a `children` array. Keys and data are parsed with `FromStr`.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The JSON to parse.
+    ///
+    pub fn from_nested_json(s: &str) -> Result<Self, ImportError>
+    where
+        D: FromStr,
+        K: FromStr,
+    {
+        Graph::from_nested_json_value(&JsonParser::parse(s)?)
+    }
+    /// Returns the node with the most children. If several nodes have the most children the first appended one is returned.
+    pub fn busiest_node(&self) -> Option<&Node<D, K>> {
+        self.nodes.iter().fold(None, |busiest, node| match busiest {
+            Some(busiest) if busiest.child_count() >= node.child_count() => Some(busiest),
+            _ => Some(node),
+        })
+    }
+    /// Returns the nodes from the root down to the node with given key. Returns None if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the last node of the path.
+    ///
+    pub fn path_nodes(&self, key: &K) -> Option<Vec<&Node<D, K>>> {
+        self.path_to(key)?
+            .iter()
+            .map(|key| find_node_with_key(&self.nodes, key))
+            .collect()
+    }
+    /// Checks that no key is listed as child by more than one node. Returns the keys with several fathers otherwise.
+    pub fn check_single_parent(&self) -> Result<(), Vec<K>> {
+        let mut seen: Vec<&K> = vec![];
+        let mut multiple_parents: Vec<K> = vec![];
+        for node in self.nodes.iter() {
+            for (index, child) in node.children.iter().enumerate() {
+                if node.children[..index].contains(child) {
+                    continue;
+                }
+                if !seen.contains(&child) {
+                    seen.push(child);
+                } else if !multiple_parents.contains(child) {
+                    multiple_parents.push(child.clone());
+                }
+            }
+        }
+        if multiple_parents.is_empty() {
+            Ok(())
+        } else {
+            Err(multiple_parents)
+        }
+    }
+    /// Labels the node with given key with the tag. Does nothing if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to tag.
+    /// * `tag` - The tag to add.
+    ///
+    pub fn add_tag(&mut self, key: &K, tag: String) {
+        if let Some(node) = self.nodes.iter_mut().find(|node| &node.key == key) {
+            node.tags.insert(tag);
+        }
+    }
+    /// Removes the tag from the node with given key. Does nothing if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to untag.
+    /// * `tag` - The tag to remove.
+    ///
+    pub fn remove_tag(&mut self, key: &K, tag: &str) {
+        if let Some(node) = self.nodes.iter_mut().find(|node| &node.key == key) {
+            node.tags.remove(tag);
+        }
+    }
+    /// Returns all nodes labeled with given tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The tag to look for.
+    ///
+    pub fn nodes_with_tag(&self, tag: &str) -> Vec<&Node<D, K>> {
+        self.nodes
+            .iter()
+            .filter(|node| node.tags.contains(tag))
+            .collect()
+    }
+    /// Returns the sum of the distances of all nodes to the root.
+    pub fn total_depth(&self) -> usize {
+        self.root()
+            .map(|root| self.level_order_with_depth_from(root))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, depth)| depth)
+            .sum()
+    }
+    /// Returns true if for every node the heights of its child subtrees differ by at most one. Only existing children are compared, so a node with a single child is always balanced.
+    pub fn is_balanced(&self) -> bool {
+        let heights = self.subtree_heights();
+        self.nodes.iter().all(|node| {
+            let child_heights = node
+                .children
+                .iter()
+                .filter_map(|child| find_position_with_key(&self.nodes, child))
+                .filter_map(|index| heights[index]);
+            let (min, max) = child_heights.fold((usize::MAX, 0), |(min, max), height| {
+                (min.min(height), max.max(height))
+            });
+            min == usize::MAX || max - min <= 1
+        })
+    }
+    /// Returns an iterator over the children of the node with given key. Yields nothing if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the father.
+    ///
+    pub fn children_iter(&self, key: &K) -> impl Iterator<Item = &Node<D, K>> + '_ {
+        find_node_with_key(&self.nodes, key)
+            .into_iter()
+            .flat_map(move |node| self.child_nodes(node))
+    }
+    /// Swaps the keys of two nodes everywhere in the graph. The nodes keep their data and position, only their keys and all references to them are exchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The key of the first node.
+    /// * `b` - The key of the second node.
+    ///
+    pub fn swap_keys(&mut self, a: &K, b: &K) -> Result<(), KeyNotFound<K>> {
+        for key in [a, b] {
+            if find_node_with_key(&self.nodes, key).is_none() {
+                return Err(KeyNotFound(key.clone()));
+            }
+        }
+        let swap = |key: &mut K| {
+            if key == a {
+                *key = b.clone();
+            } else if key == b {
+                *key = a.clone();
+            }
+        };
+        for node in self.nodes.iter_mut() {
+            swap(&mut node.key);
+            if let Some(father_key) = node.father_key.as_mut() {
+                swap(father_key);
+            }
+            node.children.iter_mut().for_each(swap);
+        }
+        Ok(())
+    }
+    /// Returns the keys in the order the nodes were appended together with the index of every nodes father in that order. The root and nodes whose father doesn't exist have no father index.
+    pub fn to_parent_array(&self) -> (Vec<K>, Vec<Option<usize>>) {
+        let keys = self.nodes.iter().map(|node| node.key.clone()).collect();
+        let parents = self
+            .nodes
+            .iter()
+            .map(|node| {
+                node.father_key
+                    .as_ref()
+                    .and_then(|father_key| find_position_with_key(&self.nodes, father_key))
+            })
+            .collect();
+        (keys, parents)
+    }
+    /// Returns the keys both nodes share on their paths from the root, ending with their lowest common ancestor. Returns an empty vector if one of the keys is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The key of the first node.
+    /// * `b` - The key of the second node.
+    ///
+    pub fn shared_ancestry(&self, a: &K, b: &K) -> Vec<K> {
+        match (self.path_to(a), self.path_to(b)) {
+            (Some(mut path_a), Some(path_b)) => {
+                path_a.truncate(shared_prefix_len(&path_a, &path_b));
+                path_a
+            }
+            _ => vec![],
+        }
+    }
+    /// Returns every downward path with exactly the given amount of edges. The paths are ordered by their first node in pre-order.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The amount of edges of every path.
+    ///
+    pub fn paths_of_length(&self, len: usize) -> Vec<Vec<K>> {
+        let mut paths = vec![];
+        for node in self.iter_order(TraversalOrder::PreOrder) {
+            self.collect_paths_of_length(node, len, &mut vec![], &mut paths);
+        }
+        paths
+    }
+    /// Returns the positions in the children of every father on the way from the root to the node with given key. Returns None if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to locate.
+    ///
+    pub fn index_path(&self, key: &K) -> Option<Vec<usize>> {
+        self.path_to(key)?
+            .windows(2)
+            .map(|edge| {
+                let father = find_node_with_key(&self.nodes, &edge[0])?;
+                father.children.iter().position(|child| child == &edge[1])
+            })
+            .collect()
+    }
+    /// Returns the node reached by descending from the root through the given positions in the children of every father. Returns None if a position is out of range.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The positions of the children to descend into.
+    ///
+    pub fn node_at_index_path(&self, path: &[usize]) -> Option<&Node<D, K>> {
+        path.iter().try_fold(self.root()?, |node, index| {
+            find_node_with_key(&self.nodes, node.children.get(*index)?)
+        })
+    }
+    /// Returns the mean amount of children of all nodes which have children. Leaves are not counted. Returns 0.0 if no node has children.
+    pub fn average_branching_factor(&self) -> f64 {
+        let (internal_nodes, children) = self
+            .nodes
+            .iter()
+            .filter(|node| !node.children.is_empty())
+            .fold((0, 0), |(internal_nodes, children), node| {
+                (internal_nodes + 1, children + node.children.len())
+            });
+        if internal_nodes == 0 {
+            0.0
+        } else {
+            children as f64 / internal_nodes as f64
+        }
+    }
+    /// Returns the graph as versioned JSON document. The current version 1 wraps the output of `to_nested_json` as `{"version":1,"root":...}`.
+    pub fn to_versioned_json(&self) -> String
+    where
+        D: Display,
+        K: Display,
+    {
+        format!(
+            "{{\"version\":{},\"root\":{}}}",
+            CURRENT_VERSION,
+            self.to_nested_json()
+        )
+    }
+    /// Returns a graph parsed from a document written by `to_versioned_json`. Documents without a version are read as plain nested JSON written by `to_nested_json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The JSON document to parse.
+    ///
+    pub fn from_versioned(s: &str) -> Result<Self, ImportError>
+    where
+        D: FromStr,
+        K: FromStr,
+    {
+        let document = JsonParser::parse(s)?;
+        let root = match document.field("version") {
+            None => &document,
+            Some(JsonValue::Number(version)) if version == "1" => document
+                .field("root")
+                .ok_or(ImportError::MissingField("root"))?,
+            Some(JsonValue::Number(version)) => {
+                return Err(ImportError::UnsupportedVersion(version.clone()))
+            }
+            Some(_) => return Err(ImportError::InvalidField("version")),
+        };
+        Graph::from_nested_json_value(root)
+    }
+    /// Returns all nodes whose distance from the root lies between min and max, both included, in the order they were appended.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The smallest depth to include.
+    /// * `max` - The largest depth to include.
+    ///
+    pub fn nodes_in_depth_range(&self, min: usize, max: usize) -> Vec<&Node<D, K>> {
+        self.nodes
+            .iter()
+            .zip(self.depths())
+            .filter(|(_, depth)| depth.is_some_and(|depth| (min..=max).contains(&depth)))
+            .map(|(node, _)| node)
+            .collect()
+    }
+    /// Keeps only the paths from the root to a leaf which contain at least one node whose data matches the predicate. All other nodes reachable from the root are removed, so the graph is empty if nothing matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Returns true for the data of every node whose paths should be kept.
+    ///
+    pub fn retain_paths_matching<P: FnMut(&D) -> bool>(&mut self, mut predicate: P) {
+        let matches: Vec<bool> = self
+            .nodes
+            .iter()
+            .map(|node| predicate(&node.data))
+            .collect();
+        let position = |key: &K| find_position_with_key(&self.nodes, key);
+        let mut keep = vec![true; self.nodes.len()];
+        if let Some(root) = self.root() {
+            let mut subtree_matches = matches.clone();
+            for node in self.post_order_from(root) {
+                if let Some(index) = position(&node.key) {
+                    subtree_matches[index] |= node
+                        .children
+                        .iter()
+                        .filter_map(position)
+                        .any(|child| subtree_matches[child]);
+                }
+            }
+            let mut path_matches = matches;
+            for node in self.pre_order_from(root) {
+                if let Some(index) = position(&node.key) {
+                    for child in node.children.iter().filter_map(position) {
+                        path_matches[child] |= path_matches[index];
+                    }
+                    keep[index] = subtree_matches[index] || path_matches[index];
+                }
+            }
+        }
+        let mut keep = keep.into_iter();
+        self.nodes.retain(|_| keep.next().unwrap_or(true));
+        let kept_keys: Vec<K> = self.nodes.iter().map(|node| node.key.clone()).collect();
+        for node in self.nodes.iter_mut() {
+            node.children.retain(|child| kept_keys.contains(child));
+        }
+    }
+    /// Returns true if a node with given key exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look for.
+    ///
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get_node(key).is_some()
+    }
+    /// Returns the node with given key if one is found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to return.
+    ///
+    pub fn get_node(&self, key: &K) -> Option<&Node<D, K>> {
+        find_node_with_key(&self.nodes, key)
+    }
+    /// Returns the node with given key mutably if one is found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to return.
+    ///
+    pub fn get_node_mut(&mut self, key: &K) -> Option<&mut Node<D, K>> {
+        self.nodes.iter_mut().find(|node| &node.key == key)
+    }
+    /// Applies the function to the data of the node with given key. Returns false if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to modify.
+    /// * `f` - The function changing the data.
+    ///
+    pub fn modify<F: FnOnce(&mut D)>(&mut self, key: &K, f: F) -> bool {
+        match self.get_node_mut(key) {
+            Some(node) => {
+                f(&mut node.data);
+                true
+            }
+            None => false,
+        }
+    }
+    /// Merges every node with exactly one child with that child until no such node is left. The merged node keeps the key and position of the father, the combined data and the children of the child. A node whose only child is the node itself or one of its ancestors is left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `merge` - Returns the combined data of given father and child data.
+    ///
+    pub fn collapse_chains<F: Fn(&D, &D) -> D>(&mut self, merge: F) {
+        while let Some((father_index, child_index)) = self.find_chain() {
+            let merged = merge(
+                &self.nodes[father_index].data,
+                &self.nodes[child_index].data,
+            );
+            let child = self.nodes.remove(child_index);
+            let father_index = if child_index < father_index {
+                father_index - 1
+            } else {
+                father_index
+            };
+            let father = &mut self.nodes[father_index];
+            father.data = merged;
+            father.children = child.children;
+            let father_key = father.key.clone();
+            for node in self.nodes.iter_mut() {
+                if node.father_key.as_ref() == Some(&child.key) {
+                    node.father_key = Some(father_key.clone());
+                }
+            }
+        }
+    }
+    /// Returns the graph in the DOT format of Graphviz with the nodes of every depth grouped into the same rank, so every level is drawn as one row.
+    pub fn to_dot_ranked(&self) -> String
+    where
+        K: Display,
+    {
+        let levels = self
+            .root()
+            .map(|root| self.level_order_with_depth_from(root))
+            .unwrap_or_default();
+        let mut dot = String::from("digraph {\n");
+        for (node, _) in levels.iter() {
+            dot.push_str(&format!("    {};\n", escape_dot(&node.key.to_string())));
+        }
+        for (node, _) in levels.iter() {
+            for child in self.child_nodes(node) {
+                dot.push_str(&format!(
+                    "    {} -> {};\n",
+                    escape_dot(&node.key.to_string()),
+                    escape_dot(&child.key.to_string())
+                ));
+            }
+        }
+        let mut levels = levels.into_iter().peekable();
+        while let Some((node, depth)) = levels.next() {
+            dot.push_str("    { rank=same; ");
+            dot.push_str(&escape_dot(&node.key.to_string()));
+            dot.push_str("; ");
+            while let Some((node, _)) = levels.next_if(|(_, next_depth)| *next_depth == depth) {
+                dot.push_str(&escape_dot(&node.key.to_string()));
+                dot.push_str("; ");
+            }
+            dot.push_str("}\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+    /// Returns the deepest node which is an ancestor of all nodes with given keys. A node counts as its own ancestor. Returns None if the list is empty or one of the keys is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys of the nodes to find the common ancestor of.
+    ///
+    pub fn lca_many(&self, keys: &[K]) -> Option<&Node<D, K>> {
+        let (first, rest) = keys.split_first()?;
+        let mut shared = self.path_to(first)?;
+        for key in rest {
+            let path = self.path_to(key)?;
+            shared.truncate(shared_prefix_len(&shared, &path));
+        }
+        find_node_with_key(&self.nodes, shared.last()?)
+    }
+    /// Returns every path from the root to a leaf as its keys joined by the separator.
+    ///
+    /// # Arguments
+    ///
+    /// * `sep` - The separator between two keys.
+    ///
+    pub fn paths_as_strings(&self, sep: &str) -> Vec<String>
+    where
+        K: Display,
+    {
+        self.root_to_leaf_paths()
+            .into_iter()
+            .map(|path| {
+                path.iter()
+                    .map(|key| key.to_string())
+                    .collect::<Vec<_>>()
+                    .join(sep)
+            })
+            .collect()
+    }
+    /// Returns an iterator over all edges as pairs of father and child node, fathers in the order they were appended.
+    pub fn iter_edges(&self) -> impl Iterator<Item = (&Node<D, K>, &Node<D, K>)> {
+        self.nodes
+            .iter()
+            .flat_map(move |father| self.child_nodes(father).map(move |child| (father, child)))
+    }
+    /// Returns true if both graphs have the same shape from the root on, ignoring keys, data and the order of children.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The graph to compare with.
+    ///
+    pub fn is_isomorphic_to(&self, other: &Graph<D, K>) -> bool {
+        self.canonical_shape() == other.canonical_shape()
+    }
+    /// Returns an iterator visiting the nodes reachable from the root in-order. For every node, the subtrees of all children but the last are visited first, then the node itself and then the subtree of the last child. For nodes with two children this is the left child, the node and the right child. A single child counts as last child and is visited after its father.
+    pub fn iter_inorder(&self) -> impl Iterator<Item = &Node<D, K>> {
+        let mut order = vec![];
+        if let Some(root) = self.root() {
+            self.collect_inorder(root, &mut order, &mut self.visited_from(root));
+        }
+        order.into_iter()
+    }
+    /// Returns the keys of all nodes whose data equals the default value of the data type, for example placeholders which were never filled in.
+    pub fn find_default_data(&self) -> Vec<&K>
+    where
+        D: Default + PartialEq,
+    {
+        let default = D::default();
+        self.nodes
+            .iter()
+            .filter(|node| node.data == default)
+            .map(|node| &node.key)
+            .collect()
+    }
+    /// Returns a hash of all nodes with their keys, relationships, tags and data in the order they were appended. Uses a fixed hash function, so the value is the same across runs and processes.
+    pub fn checksum(&self) -> u64
+    where
+        D: Hash,
+        K: Hash,
+    {
+        let mut hasher = StableHasher::new();
+        self.nodes.len().hash(&mut hasher);
+        for node in self.nodes.iter() {
+            node.key.hash(&mut hasher);
+            node.father_key.hash(&mut hasher);
+            node.children.hash(&mut hasher);
+            let mut tags: Vec<&String> = node.tags.iter().collect();
+            tags.sort_unstable();
+            tags.hash(&mut hasher);
+            node.data.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+    /// Returns all leaves whose data isn't accepted as end of a path by the predicate.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_terminal` - Returns true for the data of a proper end of a path.
+    ///
+    pub fn unexpected_leaves<P: FnMut(&D) -> bool>(&self, mut is_terminal: P) -> Vec<&Node<D, K>> {
+        self.leaves()
+            .into_iter()
+            .filter(|leaf| !is_terminal(&leaf.data))
+            .collect()
+    }
+    /// Moves every node of the list below its new father, keeping its children. The moves are checked in order as if the previous ones were applied and nothing is changed if any of them is invalid. Moved nodes become the last child of their new father.
+    ///
+    /// # Arguments
+    ///
+    /// * `moves` - Pairs of the key of the node to move and the key of its new father.
+    ///
+    pub fn reparent_many(&mut self, moves: &[(K, K)]) -> Result<(), Vec<ReparentError<K>>> {
+        let mut fathers: Vec<(K, Option<K>)> = self
+            .nodes
+            .iter()
+            .map(|node| (node.key.clone(), node.father_key.clone()))
+            .collect();
+        let mut errors = vec![];
+        for (key, new_father) in moves {
+            let index = match fathers
+                .iter()
+                .position(|(current_key, _)| current_key == key)
+            {
+                Some(index) => index,
+                None => {
+                    errors.push(ReparentError::KeyNotFound(key.clone()));
+                    continue;
+                }
+            };
+            if !fathers
+                .iter()
+                .any(|(current_key, _)| current_key == new_father)
+            {
+                errors.push(ReparentError::FatherNotFound(new_father.clone()));
+                continue;
+            }
+            if fathers[index].1.is_none() {
+                errors.push(ReparentError::RootMove(key.clone()));
+                continue;
+            }
+            let mut ancestor = Some(new_father);
+            let mut steps = 0;
+            while let Some(ancestor_key) = ancestor {
+                if ancestor_key == key || steps > fathers.len() {
+                    break;
+                }
+                ancestor = fathers
+                    .iter()
+                    .find(|(current_key, _)| current_key == ancestor_key)
+                    .and_then(|(_, father)| father.as_ref());
+                steps += 1;
+            }
+            if ancestor.is_some() {
+                errors.push(ReparentError::Cycle {
+                    key: key.clone(),
+                    new_father: new_father.clone(),
+                });
+                continue;
+            }
+            fathers[index].1 = Some(new_father.clone());
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        for (key, new_father) in moves {
+            for node in self.nodes.iter_mut() {
+                node.children.retain(|child| child != key);
+                if &node.key == new_father {
+                    node.children.push(key.clone());
+                }
+                if &node.key == key {
+                    node.father_key = Some(new_father.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Returns the data of the node with given key and all attached nodes in pre-order. Returns None if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to start at.
+    ///
+    pub fn subtree_data(&self, key: &K) -> Option<Vec<&D>> {
+        let node = find_node_with_key(&self.nodes, key)?;
+        Some(
+            self.pre_order_from(node)
+                .into_iter()
+                .map(|node| &node.data)
+                .collect(),
+        )
+    }
+    /// Copies the node with given key and all attached nodes below the new father, renaming every copied key with the function. Returns the key of the copied root.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The key of the node to copy.
+    /// * `new_father` - The key of the father of the copy.
+    /// * `key_map` - Returns the key of the copy of a node with given key.
+    ///
+    pub fn duplicate_subtree(
+        &mut self,
+        source: &K,
+        new_father: &K,
+        key_map: impl Fn(&K) -> K,
+    ) -> Result<K, DuplicateError<K>>
+    where
+        D: Clone,
+    {
+        let source_node = find_node_with_key(&self.nodes, source)
+            .ok_or_else(|| DuplicateError::SourceNotFound(source.clone()))?;
+        if find_node_with_key(&self.nodes, new_father).is_none() {
+            return Err(DuplicateError::FatherNotFound(new_father.clone()));
+        }
+        let subtree = self.pre_order_from(source_node);
+        let mut copies: Vec<Node<D, K>> = Vec::with_capacity(subtree.len());
+        for node in subtree.iter() {
+            let key = key_map(&node.key);
+            if find_node_with_key(&self.nodes, &key).is_some()
+                || find_node_with_key(&copies, &key).is_some()
+            {
+                return Err(DuplicateError::KeyCollision(key));
+            }
+            copies.push(Node {
+                data: node.data.clone(),
+                children: self
+                    .child_nodes(node)
+                    .map(|child| key_map(&child.key))
+                    .collect(),
+                father_key: if &node.key == source {
+                    Some(new_father.clone())
+                } else {
+                    node.father_key.as_ref().map(&key_map)
+                },
+                key,
+                tags: node.tags.clone(),
+                terminal: node.terminal,
+            });
+        }
+        let root_key = copies[0].key.clone();
+        if let Some(father) = self.nodes.iter_mut().find(|node| &node.key == new_father) {
+            father.children.push(root_key.clone());
+        }
+        self.nodes.extend(copies);
+        Ok(root_key)
+    }
+    /// Returns the keys of all nodes reachable from the root whose subtree, the node itself and all attached nodes, satisfies the predicate.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Returns true for the nodes of a matching subtree, given in pre-order.
+    ///
+    pub fn subtrees_where<P: Fn(&[&Node<D, K>]) -> bool>(&self, predicate: P) -> Vec<&K> {
+        let (order, sizes) = self.pre_order_with_sizes();
+        order
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| predicate(&order[*index..*index + sizes[*index]]))
+            .map(|(_, node)| &node.key)
+            .collect()
+    }
+    /// Returns a copy of every tree in the graph, one for every node without a fathers key, holding that root and all attached nodes.
+    pub fn components(&self) -> Vec<Graph<D, K>>
+    where
+        D: Clone,
+    {
+        self.nodes
+            .iter()
+            .filter(|node| node.father_key.is_none())
+            .map(|root| Graph::from_nodes(self.pre_order_from(root).into_iter().cloned().collect()))
+            .collect()
+    }
+    /// Returns true if `travel_to_node` finds a node for the route.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - A slice of child keys descending from the root.
+    ///
+    pub fn is_valid_route(&self, route: &[K]) -> bool {
+        self.travel_to_node(route).is_some()
+    }
+    /// Removes all current leaves matching the predicate and returns how many were removed. Fathers which become leaves by this are not checked again.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Returns true for every leaf to remove.
+    ///
+    pub fn trim_leaves_once<P: FnMut(&Node<D, K>) -> bool>(&mut self, mut predicate: P) -> usize {
+        let removed_keys: Vec<K> = self
+            .nodes
+            .iter()
+            .filter(|node| node.children.is_empty() && predicate(node))
+            .map(|node| node.key.clone())
+            .collect();
+        self.nodes.retain(|node| !removed_keys.contains(&node.key));
+        for node in self.nodes.iter_mut() {
+            node.children.retain(|child| !removed_keys.contains(child));
+        }
+        removed_keys.len()
+    }
+    /// Returns the node closest to the root among the nodes with given keys. If several are equally close the first appended one is returned. Keys which are not found are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys of the candidate nodes.
+    ///
+    pub fn shallowest(&self, keys: &[K]) -> Option<&Node<D, K>> {
+        let depths = self.depths();
+        keys.iter()
+            .filter_map(|key| find_position_with_key(&self.nodes, key))
+            .filter_map(|index| depths[index].map(|depth| (depth, index)))
+            .min()
+            .map(|(_, index)| &self.nodes[index])
+    }
+    /// Returns the node farthest from the root. If several are equally far the first appended one is returned.
+    pub fn deepest(&self) -> Option<&Node<D, K>> {
+        self.depths()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, depth)| depth.map(|depth| (depth, Reverse(index))))
+            .max()
+            .map(|(_, Reverse(index))| &self.nodes[index])
+    }
+    /// Returns how many paths from the root to a leaf have each amount of edges.
+    pub fn path_length_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for path in self.root_to_leaf_paths() {
+            *histogram.entry(path.len() - 1).or_insert(0) += 1;
+        }
+        histogram
+    }
+    /// Returns a copy of the graph with every edge pointing the other way. Former leaves become roots and the former root becomes a leaf, so the result usually has several roots. A node only holds one fathers key, so a former father of several children gets the first of them as father, while all of them list it as child.
+    pub fn reversed(&self) -> Graph<D, K>
+    where
+        D: Clone,
+    {
+        Graph::from_nodes(
+            self.nodes
+                .iter()
+                .map(|node| Node {
+                    data: node.data.clone(),
+                    children: node
+                        .father_key
+                        .iter()
+                        .filter(|father_key| find_node_with_key(&self.nodes, father_key).is_some())
+                        .cloned()
+                        .collect(),
+                    father_key: self.child_nodes(node).next().map(|child| child.key.clone()),
+                    key: node.key.clone(),
+                    tags: node.tags.clone(),
+                    terminal: node.terminal,
+                })
+                .collect(),
+        )
+    }
+    /// Returns true if a node exists for every given key. Stops at the first missing key.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to look for.
+    ///
+    pub fn all_exist(&self, keys: &[K]) -> bool {
+        keys.iter().all(|key| self.contains_key(key))
+    }
+    /// Returns the keys of the tree as S-expression like `(Start (Essen) (Sitzplatz (Gang)))`, every node wrapped in parentheses together with its children. Keys which are empty or hold whitespace, parentheses, quotes or backslashes are written in quotes with quotes and backslashes escaped. Returns an empty string for an empty graph.
+    pub fn to_sexpr(&self) -> String
+    where
+        K: Display,
+    {
+        let mut sexpr = String::new();
+        if let Some(root) = self.root() {
+            self.write_sexpr(root, &mut sexpr, &mut self.visited_from(root));
+        }
+        sexpr
+    }
+    /// Returns a graph parsed from an S-expression as written by `to_sexpr`. Keys are plain atoms or quoted strings with escaped quotes and backslashes. The data of every node is derived from its key. An empty or blank input returns an empty graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The S-expression to parse.
+    /// * `data` - Returns the data for the node with given key.
+    ///
+    pub fn from_sexpr(s: &str, data: impl Fn(&K) -> D) -> Result<Self, ParseError>
+    where
+        K: FromStr,
+    {
+        let mut graph = Graph::new();
+        let mut parser = SexprParser {
+            text: s,
+            position: 0,
+        };
+        parser.skip_whitespace();
+        if parser.peek().is_some() {
+            graph.import_sexpr(&mut parser, None, &data)?;
+            parser.skip_whitespace();
+            if parser.peek().is_some() {
+                return Err(ParseError::UnexpectedCharacter(parser.position));
+            }
+        }
+        Ok(graph)
+    }
+    /// Returns for every node reachable from the root, in pre-order, the value accumulated over the data on the path from the root down to the node.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The value the root is accumulated onto.
+    /// * `f` - Returns the accumulated value of a node from the value of its father and its data.
+    ///
+    pub fn scan_paths<B: Clone, F: FnMut(&B, &D) -> B>(&self, init: B, mut f: F) -> Vec<(K, B)> {
+        let mut values = vec![];
+        let mut stack: Vec<(&Node<D, K>, B)> = vec![];
+        let mut visited = vec![];
+        if let Some(root) = self.root() {
+            visited = self.visited_from(root);
+            stack.push((root, f(&init, &root.data)));
+        }
+        while let Some((node, value)) = stack.pop() {
+            let children: Vec<_> = self
+                .unvisited_children(node, &mut visited)
+                .into_iter()
+                .map(|child| (child, f(&value, &child.data)))
+                .collect();
+            stack.extend(children.into_iter().rev());
+            values.push((node.key.clone(), value));
+        }
+        values
+    }
+    /// Returns the first key, in the order the nodes were appended, which is used by more than one node.
+    pub fn first_duplicate_key(&self) -> Option<&K> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .find(|(index, node)| find_node_with_key(&self.nodes[..*index], &node.key).is_some())
+            .map(|(_, node)| &node.key)
+    }
+    /// Returns all nodes at most the given amount of edges away from the node with given key, going up to fathers as well as down to children. The nodes are returned in the order they were appended, including the node itself. Returns an empty vector if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node in the center.
+    /// * `hops` - The largest amount of edges to a returned node.
+    ///
+    pub fn within_hops(&self, key: &K, hops: usize) -> Vec<&Node<D, K>> {
+        let mut distances: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut queue = VecDeque::new();
+        if let Some(index) = find_position_with_key(&self.nodes, key) {
+            distances[index] = Some(0);
+            queue.push_back(index);
+        }
+        while let Some(index) = queue.pop_front() {
+            let distance = distances[index].unwrap_or(0);
+            if distance == hops {
+                continue;
+            }
+            let node = &self.nodes[index];
+            let neighbours = node.father_key.iter().chain(node.children.iter());
+            for neighbour in neighbours.filter_map(|key| find_position_with_key(&self.nodes, key)) {
+                if distances[neighbour].is_none() {
+                    distances[neighbour] = Some(distance + 1);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        self.nodes
+            .iter()
+            .zip(distances)
+            .filter(|(_, distance)| distance.is_some())
+            .map(|(node, _)| node)
+            .collect()
+    }
+    /// Returns the centroid of the tree, the node whose removal leaves the smallest largest remaining part. If several nodes qualify the first one in pre-order is returned. Returns None if the graph is empty.
+    pub fn centroid(&self) -> Option<&Node<D, K>> {
+        let (order, sizes) = self.pre_order_with_sizes();
+        let total = order.len();
+        (0..total)
+            .min_by_key(|&index| {
+                let mut largest = total - sizes[index];
+                let mut child = index + 1;
+                while child < index + sizes[index] {
+                    largest = largest.max(sizes[child]);
+                    child += sizes[child];
+                }
+                largest
+            })
+            .map(|index| order[index])
+    }
+    /// Writes the graph as edge records. The first line is a root record holding only the key of the root, followed by one line per edge holding the keys of father and child separated by a tab. Backslashes, tabs, line feeds and carriage returns in keys are written as `\\`, `\t`, `\n` and `\r`, the empty key as `\0`. The lines are written directly, so the graph is never stringified as a whole. Writes nothing for an empty graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `w` - The writer to write the edges to.
+    ///
+    pub fn write_edges<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()>
+    where
+        K: Display,
+    {
+        if let Some(root) = self.root() {
+            writeln!(w, "{}", escape_edge_key(&root.key.to_string()))?;
+        }
+        for (father, child) in self.iter_edges() {
+            writeln!(
+                w,
+                "{}\t{}",
+                escape_edge_key(&father.key.to_string()),
+                escape_edge_key(&child.key.to_string())
+            )?;
+        }
+        Ok(())
+    }
+    /// Returns a graph read from lines as written by `write_edges`. Every line holds the escaped keys of father and child separated by a tab or a single escaped key of a node without father, empty lines are skipped. The root is the only key that never appears as child. An empty input returns an empty graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - The reader to read the edges from.
+    /// * `data` - Returns the data for the node with given key.
+    ///
+    pub fn read_edges<R: std::io::BufRead>(
+        r: R,
+        data: impl Fn(&K) -> D,
+    ) -> Result<Self, ImportError>
+    where
+        K: FromStr,
+    {
+        let parse_key = |field: &str, number: usize| {
+            let key = unescape_edge_key(field).ok_or(ImportError::InvalidLine(number + 1))?;
+            key.parse::<K>().map_err(|_| ImportError::InvalidKey(key))
+        };
+        let mut nodes: Vec<Node<D, K>> = Vec::new();
+        for (number, line) in r.lines().enumerate() {
+            let line = line.map_err(|error| ImportError::Io(error.to_string()))?;
+            if line.is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            let (father, child) = match fields.as_slice() {
+                [root] => {
+                    let root_key = parse_key(root, number)?;
+                    if find_position_with_key(&nodes, &root_key).is_none() {
+                        nodes.push(Node::new(data(&root_key), root_key, None));
+                    }
+                    continue;
+                }
+                [father, child] => (*father, *child),
+                _ => return Err(ImportError::InvalidLine(number + 1)),
+            };
+            let (father_key, child_key) = (parse_key(father, number)?, parse_key(child, number)?);
+            let father_index = match find_position_with_key(&nodes, &father_key) {
+                Some(index) => index,
+                None => {
+                    nodes.push(Node::new(data(&father_key), father_key.clone(), None));
+                    nodes.len() - 1
+                }
+            };
+            match find_position_with_key(&nodes, &child_key) {
+                Some(index) if nodes[index].father_key.is_some() => {
+                    return Err(ImportError::DuplicateKey(
+                        unescape_edge_key(child).unwrap_or_default(),
+                    ));
+                }
+                Some(index) => nodes[index].father_key = Some(father_key),
+                None => nodes.push(Node::new(data(&child_key), child_key.clone(), father_key)),
+            }
+            nodes[father_index].children.push(child_key);
+        }
+        let graph = Graph::from_nodes(nodes);
+        let roots = graph
+            .nodes
+            .iter()
+            .filter(|node| node.father_key.is_none())
+            .count();
+        let reachable = graph
+            .root()
+            .map_or(0, |root| graph.pre_order_from(root).len());
+        if graph.is_empty() || (roots == 1 && reachable == graph.len()) {
+            Ok(graph)
+        } else {
+            Err(ImportError::InvalidRoot)
+        }
+    }
+    /// Returns how many nodes would be lost if the node with given key and all attached nodes were removed. Returns None if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to remove.
+    ///
+    pub fn impact_of_removal(&self, key: &K) -> Option<usize> {
+        let node = find_node_with_key(&self.nodes, key)?;
+        Some(self.pre_order_from(node).len())
+    }
+    /// Returns the keys of the node with given key and all attached nodes with children before their fathers, so they can be deleted one by one. Returns None if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to delete.
+    ///
+    pub fn deletion_order(&self, key: &K) -> Option<Vec<K>> {
+        let node = find_node_with_key(&self.nodes, key)?;
+        Some(
+            self.post_order_from(node)
+                .into_iter()
+                .map(|node| node.key.clone())
+                .collect(),
+        )
+    }
+    /// Returns true if the nodes with given keys are two different nodes with the same father. Returns false if one of the keys is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The key of the first node.
+    /// * `b` - The key of the second node.
+    ///
+    pub fn are_siblings(&self, a: &K, b: &K) -> bool {
+        if a == b {
+            return false;
+        }
+        match (
+            find_node_with_key(&self.nodes, a),
+            find_node_with_key(&self.nodes, b),
+        ) {
+            (Some(a), Some(b)) => a.father_key.is_some() && a.father_key == b.father_key,
+            _ => false,
+        }
+    }
+    /// Returns a map from every key to the data of its node.
+    pub fn to_data_map(&self) -> HashMap<K, &D>
+    where
+        K: Hash + Eq,
+    {
+        self.nodes
+            .iter()
+            .map(|node| (node.key.clone(), &node.data))
+            .collect()
+    }
+    /// Returns the node with the greatest data under the given comparison. If several nodes have the greatest data the first appended one is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmp` - Compares the data of two nodes.
+    ///
+    pub fn max_by_data<F: FnMut(&D, &D) -> Ordering>(&self, mut cmp: F) -> Option<&Node<D, K>> {
+        self.nodes.iter().rev().max_by(|a, b| cmp(&a.data, &b.data))
+    }
+    /// Returns the depth shared by all leaves. Returns None if the leaves have different depths, a leaf isn't reachable from the root or the graph is empty.
+    pub fn leaves_uniform_depth(&self) -> Option<usize> {
+        let depths = self.depths();
+        let mut leaf_depths = self
+            .nodes
+            .iter()
+            .zip(depths)
+            .filter(|(node, _)| node.children.is_empty())
+            .map(|(_, depth)| depth);
+        let first = leaf_depths.next()??;
+        leaf_depths
+            .all(|depth| depth == Some(first))
+            .then_some(first)
+    }
+    /// Returns the keys of all nodes as tree drawn with box-drawing characters, one node per line, children below their father.
+    pub fn to_tree_string_unicode(&self) -> String
+    where
+        K: Display,
+    {
+        let mut tree = String::new();
+        if let Some(root) = self.root() {
+            tree.push_str(&format!("{}\n", root.key));
+            self.write_tree_unicode(root, "", &mut tree, &mut self.visited_from(root));
+        }
+        tree
+    }
+    /// Marks the node with given key as terminal, so no children can be appended to it anymore. Does nothing if the key is not found.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to mark.
+    ///
+    pub fn mark_terminal(&mut self, key: &K) {
+        if let Some(node) = self.nodes.iter_mut().find(|node| &node.key == key) {
+            node.terminal = true;
+        }
+    }
+    /// Returns the amount of edges on the longest path between any two nodes. Returns 0 if the graph has less than two nodes.
+    pub fn diameter(&self) -> usize {
+        let heights = self.subtree_heights();
+        self.nodes
+            .iter()
+            .map(|node| {
+                self.tallest_children(node, &heights)
+                    .iter()
+                    .take(2)
+                    .map(|(height, _)| height + 1)
+                    .sum()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+    /// Returns the two nodes at the ends of the longest path, the one reached through the earlier child first. If several paths are the longest the one appended first is returned. Returns the root twice if it is the only node and None if the graph is empty.
+    pub fn diameter_endpoints(&self) -> Option<NodePair<'_, D, K>> {
+        let heights = self.subtree_heights();
+        let (_, (center, mut tallest)) = self
+            .nodes
+            .iter()
+            .filter(|node| {
+                find_position_with_key(&self.nodes, &node.key)
+                    .is_some_and(|index| heights[index].is_some())
+            })
+            .map(|node| {
+                let mut tallest = self.tallest_children(node, &heights);
+                tallest.truncate(2);
+                let length: usize = tallest.iter().map(|(height, _)| height + 1).sum();
+                (length, (node, tallest))
+            })
+            .min_by_key(|(length, _)| Reverse(*length))?;
+        tallest.sort_by_key(|(_, child)| center.children.iter().position(|key| key == &child.key));
+        let mut endpoints = tallest
+            .into_iter()
+            .map(|(_, child)| self.deepest_below(child, &heights));
+        let first = endpoints.next().unwrap_or(center);
+        Some((first, endpoints.next().unwrap_or(center)))
+    }
+    /// Replaces the root with the given node and attaches the children of the old root to it. Returns the old root without children. The new root may reuse the key of the old root.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_root` - The node to become the root. It cant have a fathers key.
+    ///
+    pub fn set_root(&mut self, mut new_root: Node<D, K>) -> Result<Node<D, K>, SetRootError<K>> {
+        if new_root.father_key.is_some() {
+            return Err(SetRootError::RootWithFather(new_root.key));
+        }
+        let index = self
+            .nodes
+            .iter()
+            .position(|node| node.father_key.is_none())
+            .ok_or(SetRootError::EmptyGraph)?;
+        if new_root.key != self.nodes[index].key && self.contains_key(&new_root.key) {
+            return Err(SetRootError::KeyCollision(new_root.key));
+        }
+        let children = std::mem::take(&mut self.nodes[index].children);
+        for node in self
+            .nodes
+            .iter_mut()
+            .filter(|node| children.contains(&node.key))
+        {
+            node.father_key = Some(new_root.key.clone());
+        }
+        new_root.children = children;
+        Ok(std::mem::replace(&mut self.nodes[index], new_root))
+    }
+    /// Returns the amount of different data values in the graph.
+    pub fn distinct_data_count(&self) -> usize
+    where
+        D: Hash + Eq,
+    {
+        self.nodes
+            .iter()
+            .map(|node| &node.data)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+    /// Returns the keys of all nodes sharing their data with another node, grouped by data. The groups are ordered by their first node and hold the keys in the order the nodes were appended.
+    pub fn duplicate_data_groups(&self) -> Vec<Vec<&K>>
+    where
+        D: Hash + Eq,
+    {
+        let mut group_of_data: HashMap<&D, usize> = HashMap::new();
+        let mut groups: Vec<Vec<&K>> = vec![];
+        for node in &self.nodes {
+            let group = *group_of_data.entry(&node.data).or_insert_with(|| {
+                groups.push(vec![]);
+                groups.len() - 1
+            });
+            groups[group].push(&node.key);
+        }
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+    /// Returns the keys of all nodes reachable from the root so that every father comes before its children. This is the pre-order of the graph.
+    pub fn topological_order(&self) -> Vec<&K> {
+        self.root()
+            .map(|root| self.pre_order_from(root))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|node| &node.key)
+            .collect()
+    }
+    /// Merges the other graph into this one if both have the same root key. Nodes found in both graphs need the same father, keep the data of this graph and get the children of both. All other nodes of the other graph are appended. An empty graph takes all nodes of the other one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The graph to merge into this one.
+    ///
+    pub fn merge_at_root(&mut self, other: Graph<D, K>) -> Result<(), MergeError<K>> {
+        let (Some(root), Some(other_root)) = (self.root(), other.root()) else {
+            if self.is_empty() {
+                self.nodes = other.nodes;
+            }
+            return Ok(());
+        };
+        if root.key != other_root.key {
+            return Err(MergeError::RootMismatch(other_root.key.clone()));
+        }
+        if let Some(conflict) = other.nodes.iter().find(|node| {
+            find_node_with_key(&self.nodes, &node.key)
+                .is_some_and(|existing| existing.father_key != node.father_key)
+        }) {
+            return Err(MergeError::KeyConflict(conflict.key.clone()));
+        }
+        for node in other.nodes {
+            match find_position_with_key(&self.nodes, &node.key) {
+                Some(index) => {
+                    let existing = &mut self.nodes[index];
+                    for child in node.children {
+                        if !existing.children.contains(&child) {
+                            existing.children.push(child);
+                        }
+                    }
+                }
+                None => self.nodes.push(node),
+            }
+        }
+        Ok(())
+    }
+    /// Returns the share of nodes with more than one child among all nodes with children. Returns 0.0 if no node has children.
+    pub fn branching_ratio(&self) -> f64 {
+        let internal = self.nodes.iter().filter(|node| !node.children.is_empty());
+        let (count, branching) = internal.fold((0, 0), |(count, branching), node| {
+            (count + 1, branching + usize::from(node.children.len() > 1))
+        });
+        if count == 0 {
+            0.0
+        } else {
+            branching as f64 / count as f64
+        }
+    }
+    /// Returns a copy of all nodes and limits of the graph which can be restored later.
+    pub fn snapshot(&self) -> GraphSnapshot<D, K>
+    where
+        D: Clone,
+    {
+        GraphSnapshot {
+            nodes: self.nodes.clone(),
+            max_children: self.max_children,
+        }
+    }
+    /// Replaces all nodes and limits of the graph with the ones of the snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `snap` - The snapshot to roll back to.
+    ///
+    pub fn restore(&mut self, snap: GraphSnapshot<D, K>) {
+        self.nodes = snap.nodes;
+        self.max_children = snap.max_children;
+    }
+    /// Returns the nearest ancestor of the node with given key for which the predicate returns true, starting at its father. Returns None if the key is not found or no ancestor matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to start from.
+    /// * `predicate` - Returns true for the ancestor to find.
+    ///
+    pub fn nearest_ancestor<P: FnMut(&Node<D, K>) -> bool>(
+        &self,
+        key: &K,
+        mut predicate: P,
+    ) -> Option<&Node<D, K>> {
+        let mut node = find_node_with_key(&self.nodes, key)?;
+        for _ in 0..self.nodes.len() {
+            node = find_node_with_key(&self.nodes, node.father_key.as_ref()?)?;
+            if predicate(node) {
+                return Some(node);
+            }
+        }
+        None
+    }
+    /// Returns an iterator over all nodes reachable from the root in pre-order, each with the nodes of its subtree, the node itself and all attached nodes in pre-order.
+    pub fn subtrees(&self) -> impl Iterator<Item = (&Node<D, K>, Vec<&Node<D, K>>)> {
+        let (order, sizes) = self.pre_order_with_sizes();
+        (0..order.len())
+            .map(move |index| (order[index], order[index..index + sizes[index]].to_vec()))
+    }
+    /// Returns true if a node lists the same child key more than once.
+    pub fn has_ambiguous_children(&self) -> bool {
+        self.nodes.iter().any(|node| {
+            node.children
+                .iter()
+                .enumerate()
+                .any(|(index, child)| node.children[..index].contains(child))
+        })
+    }
+    /// Returns the nodes reachable from the root as Mermaid flowchart. Keys made of letters, digits and underscores which aren't Mermaid keywords are used as ids directly, all other keys get an unused id from their position and are shown as escaped label.
+    pub fn to_mermaid(&self) -> String
+    where
+        K: Display,
+    {
+        let order = self
+            .root()
+            .map(|root| self.pre_order_from(root))
+            .unwrap_or_default();
+        let ids = self.mermaid_ids();
+        let id_of = |node: &Node<D, K>| {
+            find_position_with_key(&self.nodes, &node.key).map_or("", |index| ids[index].as_str())
+        };
+        let mut mermaid = String::from("graph TD\n");
+        for node in order.iter() {
+            let key = node.key.to_string();
+            if id_of(node) == key {
+                mermaid.push_str(&format!("    {}\n", key));
+            } else {
+                mermaid.push_str(&format!("    {}[{}]\n", id_of(node), escape_mermaid(&key)));
+            }
+        }
+        for node in order.iter() {
+            for child in self.child_nodes(node) {
+                mermaid.push_str(&format!("    {} --> {}\n", id_of(node), id_of(child)));
+            }
+        }
+        mermaid
+    }
+    /// Checks if a child could be appended to the father with given key.
+    fn check_father(&self, father_key: &K) -> Result<(), AppendError<K>> {
+        let father = find_node_with_key(&self.nodes, father_key)
+            .ok_or_else(|| AppendError::FatherNotFound(father_key.clone()))?;
+        if father.terminal {
+            return Err(AppendError::TerminalFather(father_key.clone()));
+        }
+        match self.max_children {
+            Some(max) if father.children.len() >= max => {
+                Err(AppendError::TooManyChildren(father_key.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+    /// Returns a graph holding the given nodes without any limits.
+    fn from_nodes(nodes: Vec<Node<D, K>>) -> Self {
+        Graph {
+            nodes,
+            max_children: None,
+        }
+    }
+    /// Returns the first node without a fathers key.
+    fn root(&self) -> Option<&Node<D, K>> {
+        self.nodes.iter().find(|node| node.father_key.is_none())
+    }
+    /// Returns the resolved child nodes of given node.
+    fn child_nodes<'a>(&'a self, node: &'a Node<D, K>) -> impl Iterator<Item = &'a Node<D, K>> {
+        node.children
+            .iter()
+            .filter_map(|child| find_node_with_key(&self.nodes, child))
+    }
+    /// Marks given node as visited. Returns false if it was visited before, so traversals end on cycles.
+    fn visit(&self, node: &Node<D, K>, visited: &mut [bool]) -> bool {
+        match find_position_with_key(&self.nodes, &node.key) {
+            Some(index) => !std::mem::replace(&mut visited[index], true),
+            None => true,
+        }
+    }
+    /// Returns the visited marks for a traversal starting at given node, with only that node marked.
+    fn visited_from(&self, start: &Node<D, K>) -> Vec<bool> {
+        let mut visited = vec![false; self.nodes.len()];
+        self.visit(start, &mut visited);
+        visited
+    }
+    /// Returns the child nodes of given node which weren't visited before and marks them as visited.
+    fn unvisited_children<'a>(
+        &'a self,
+        node: &'a Node<D, K>,
+        visited: &mut [bool],
+    ) -> Vec<&'a Node<D, K>> {
+        self.child_nodes(node)
+            .filter(|child| self.visit(child, visited))
+            .collect()
+    }
+    /// Returns given node and all attached nodes, every father before its children.
+    fn pre_order_from<'a>(&'a self, start: &'a Node<D, K>) -> Vec<&'a Node<D, K>> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut order = vec![];
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            if !self.visit(node, &mut visited) {
+                continue;
+            }
+            order.push(node);
+            let children: Vec<_> = self.child_nodes(node).collect();
+            stack.extend(children.into_iter().rev());
+        }
+        order
+    }
+    /// Returns given node and all attached nodes, every father after its children.
+    fn post_order_from<'a>(&'a self, start: &'a Node<D, K>) -> Vec<&'a Node<D, K>> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut order = vec![];
+        let mut stack = vec![(start, false)];
+        while let Some((node, children_visited)) = stack.pop() {
+            if children_visited {
+                order.push(node);
+            } else if self.visit(node, &mut visited) {
+                stack.push((node, true));
+                let children: Vec<_> = self.child_nodes(node).collect();
+                stack.extend(children.into_iter().rev().map(|child| (child, false)));
+            }
+        }
+        order
+    }
+    /// Returns given node and all attached nodes level by level.
+    fn level_order_from<'a>(&'a self, start: &'a Node<D, K>) -> Vec<&'a Node<D, K>> {
+        self.level_order_with_depth_from(start)
+            .into_iter()
+            .map(|(node, _)| node)
+            .collect()
+    }
+    /// Returns given node and all attached nodes level by level together with their distance to given node.
+    fn level_order_with_depth_from<'a>(
+        &'a self,
+        start: &'a Node<D, K>,
+    ) -> Vec<(&'a Node<D, K>, usize)> {
+        let mut visited = self.visited_from(start);
+        let mut order = vec![(start, 0)];
+        let mut index = 0;
+        while index < order.len() {
+            let (node, depth) = order[index];
+            let children: Vec<_> = self
+                .child_nodes(node)
+                .filter(|child| self.visit(child, &mut visited))
+                .map(|child| (child, depth + 1))
+                .collect();
+            order.extend(children);
+            index += 1;
+        }
+        order
+    }
+    /// Returns the keys from the root down to the node with given key. Returns None if the key is not found or the node isn't attached to a root.
+    fn path_to(&self, key: &K) -> Option<Vec<K>> {
+        let mut path = vec![];
+        let mut current_node = find_node_with_key(&self.nodes, key)?;
+        loop {
+            path.push(current_node.key.clone());
+            if path.len() > self.nodes.len() {
+                return None;
+            }
+            match &current_node.father_key {
+                Some(father_key) => {
+                    current_node = find_node_with_key(&self.nodes, father_key)?;
+                }
+                None => break,
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+    /// Writes given node and all attached nodes as nested JSON object.
+    fn write_nested_json(&self, node: &Node<D, K>, json: &mut String, visited: &mut [bool])
+    where
+        D: Display,
+        K: Display,
+    {
+        json.push_str("{\"key\":");
+        json.push_str(&escape_json(&node.key.to_string()));
+        json.push_str(",\"data\":");
+        json.push_str(&escape_json(&node.data.to_string()));
+        json.push_str(",\"children\":[");
+        for (index, child) in self
+            .unvisited_children(node, visited)
+            .into_iter()
+            .enumerate()
+        {
+            if index > 0 {
+                json.push(',');
+            }
+            self.write_nested_json(child, json, visited);
+        }
+        json.push_str("]}");
+    }
+    /// Returns a graph built from the parsed nested JSON of its root. An empty graph is written as `null`.
+    fn from_nested_json_value(root: &JsonValue) -> Result<Self, ImportError>
+    where
+        D: FromStr,
+        K: FromStr,
+    {
+        let mut graph = Graph::new();
+        if !matches!(root, JsonValue::Null) {
+            graph.import_nested_json(root, None)?;
+        }
+        Ok(graph)
+    }
+    /// Appends the node described by given JSON object and all its children.
+    fn import_nested_json(
+        &mut self,
+        value: &JsonValue,
+        father_key: Option<K>,
+    ) -> Result<(), ImportError>
+    where
+        D: FromStr,
+        K: FromStr,
+    {
+        let raw_key = value.string_field("key")?;
+        let key: K = raw_key
+            .parse()
+            .map_err(|_| ImportError::InvalidKey(raw_key.to_string()))?;
+        if find_node_with_key(&self.nodes, &key).is_some() {
+            return Err(ImportError::DuplicateKey(raw_key.to_string()));
+        }
+        let raw_data = value.string_field("data")?;
+        let data: D = raw_data
+            .parse()
+            .map_err(|_| ImportError::InvalidData(raw_data.to_string()))?;
+        if let Some(father_key) = &father_key {
+            if let Some(father) = self.nodes.iter_mut().find(|node| &node.key == father_key) {
+                father.children.push(key.clone());
+            }
+        }
+        self.nodes.push(Node::new(data, key.clone(), father_key));
+        match value.field("children") {
+            None | Some(JsonValue::Null) => {}
+            Some(JsonValue::Array(children)) => {
+                for child in children {
+                    self.import_nested_json(child, Some(key.clone()))?;
+                }
+            }
+            Some(_) => return Err(ImportError::InvalidField("children")),
+        }
+        Ok(())
+    }
+    /// Returns the height of the subtree of every node reachable from the root, indexed by the nodes position. A leaf has height 0.
+    fn subtree_heights(&self) -> Vec<Option<usize>> {
+        let mut heights = vec![None; self.nodes.len()];
+        if let Some(root) = self.root() {
+            for node in self.post_order_from(root) {
+                let height = node
+                    .children
+                    .iter()
+                    .filter_map(|child| find_position_with_key(&self.nodes, child))
+                    .filter_map(|index| heights[index])
+                    .map(|height| height + 1)
+                    .max()
+                    .unwrap_or(0);
+                if let Some(index) = find_position_with_key(&self.nodes, &node.key) {
+                    heights[index] = Some(height);
+                }
+            }
+        }
+        heights
+    }
+    /// Collects all downward paths starting at given node with the remaining amount of edges.
+    fn collect_paths_of_length(
+        &self,
+        node: &Node<D, K>,
+        remaining: usize,
+        path: &mut Vec<K>,
+        paths: &mut Vec<Vec<K>>,
+    ) {
+        path.push(node.key.clone());
+        if remaining == 0 {
+            paths.push(path.clone());
+        } else {
+            for child in self.child_nodes(node) {
+                self.collect_paths_of_length(child, remaining - 1, path, paths);
+            }
+        }
+        path.pop();
+    }
+    /// Returns the distance to the root of every node reachable from it, indexed by the nodes position.
+    fn depths(&self) -> Vec<Option<usize>> {
+        let mut depths = vec![None; self.nodes.len()];
+        if let Some(root) = self.root() {
+            for (node, depth) in self.level_order_with_depth_from(root) {
+                if let Some(index) = find_position_with_key(&self.nodes, &node.key) {
+                    depths[index] = Some(depth);
+                }
+            }
+        }
+        depths
+    }
+    /// Returns true if the node with given key is given node or one of its ancestors. Also returns true if the fathers of given node form a cycle.
+    fn is_on_path_to(&self, key: &K, node: &Node<D, K>) -> bool {
+        let mut current = node;
+        for _ in 0..self.nodes.len() {
+            if &current.key == key {
+                return true;
+            }
+            match current
+                .father_key
+                .as_ref()
+                .and_then(|father_key| find_node_with_key(&self.nodes, father_key))
+            {
+                Some(father) => current = father,
+                None => return false,
+            }
+        }
+        true
+    }
+    /// Returns the positions of the first node with exactly one child and of that child, skipping children which are the node itself or one of its ancestors.
+    fn find_chain(&self) -> Option<(usize, usize)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .find_map(|(index, node)| match node.children.as_slice() {
+                [child] if !self.is_on_path_to(child, node) => {
+                    find_position_with_key(&self.nodes, child)
+                        .map(|child_index| (index, child_index))
+                }
+                _ => None,
+            })
+    }
+    /// Returns the keys of every path from the root to a leaf in pre-order.
+    fn root_to_leaf_paths(&self) -> Vec<Vec<K>> {
+        self.iter_order(TraversalOrder::PreOrder)
+            .filter(|node| self.child_nodes(node).next().is_none())
+            .filter_map(|leaf| self.path_to(&leaf.key))
+            .collect()
+    }
+    /// Returns an encoding of the shape of the tree below the root which is equal for all trees with the same shape.
+    fn canonical_shape(&self) -> Option<String> {
+        let root = self.root()?;
+        let mut shapes: Vec<Option<String>> = vec![None; self.nodes.len()];
+        for node in self.post_order_from(root) {
+            let mut child_shapes: Vec<&str> = node
+                .children
+                .iter()
+                .filter_map(|child| find_position_with_key(&self.nodes, child))
+                .filter_map(|index| shapes[index].as_deref())
+                .collect();
+            child_shapes.sort_unstable();
+            let shape = format!("({})", child_shapes.concat());
+            if let Some(index) = find_position_with_key(&self.nodes, &node.key) {
+                shapes[index] = Some(shape);
+            }
+        }
+        find_position_with_key(&self.nodes, &root.key).and_then(|index| shapes[index].take())
+    }
+    /// Collects given node and all attached nodes in-order.
+    fn collect_inorder<'a>(
+        &'a self,
+        node: &'a Node<D, K>,
+        order: &mut Vec<&'a Node<D, K>>,
+        visited: &mut [bool],
+    ) {
+        let children = self.unvisited_children(node, visited);
+        match children.split_last() {
+            Some((last, rest)) => {
+                for child in rest {
+                    self.collect_inorder(child, order, visited);
+                }
+                order.push(node);
+                self.collect_inorder(last, order, visited);
+            }
+            None => order.push(node),
+        }
+    }
+    /// Returns the nodes reachable from the root in pre-order together with the size of the subtree of every node in that order. The subtree of a node are the nodes from its index up to its index plus its size. Every node is counted once, under the father it is reached from first.
+    fn pre_order_with_sizes(&self) -> (Vec<&Node<D, K>>, Vec<usize>) {
+        let mut order = vec![];
+        let mut sizes = vec![];
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack: Vec<(&Node<D, K>, Option<usize>)> =
+            self.root().map(|root| (root, None)).into_iter().collect();
+        while let Some((node, start)) = stack.pop() {
+            match start {
+                Some(start) => sizes[start] = order.len() - start,
+                None if self.visit(node, &mut visited) => {
+                    stack.push((node, Some(order.len())));
+                    order.push(node);
+                    sizes.push(1);
+                    let children: Vec<_> = self.child_nodes(node).collect();
+                    stack.extend(children.into_iter().rev().map(|child| (child, None)));
+                }
+                None => {}
+            }
+        }
+        (order, sizes)
+    }
+    /// Writes given node and all attached nodes as S-expression.
+    fn write_sexpr(&self, node: &Node<D, K>, sexpr: &mut String, visited: &mut [bool])
+    where
+        K: Display,
+    {
+        sexpr.push('(');
+        sexpr.push_str(&escape_sexpr(&node.key.to_string()));
+        for child in self.unvisited_children(node, visited) {
+            sexpr.push(' ');
+            self.write_sexpr(child, sexpr, visited);
+        }
+        sexpr.push(')');
+    }
+    /// Appends the node starting at the current position of the parser and all its children.
+    fn import_sexpr(
+        &mut self,
+        parser: &mut SexprParser,
+        father_key: Option<K>,
+        data: &impl Fn(&K) -> D,
+    ) -> Result<(), ParseError>
+    where
+        K: FromStr,
+    {
+        parser.expect('(')?;
+        parser.skip_whitespace();
+        let raw_key = parser.key()?;
+        let key: K = raw_key
+            .parse()
+            .map_err(|_| ParseError::InvalidKey(raw_key.clone()))?;
+        if find_node_with_key(&self.nodes, &key).is_some() {
+            return Err(ParseError::DuplicateKey(raw_key));
+        }
+        if let Some(father_key) = &father_key {
+            if let Some(father) = self.nodes.iter_mut().find(|node| &node.key == father_key) {
+                father.children.push(key.clone());
+            }
+        }
+        self.nodes
+            .push(Node::new(data(&key), key.clone(), father_key));
+        loop {
+            parser.skip_whitespace();
+            match parser.peek() {
+                Some('(') => self.import_sexpr(parser, Some(key.clone()), data)?,
+                Some(')') => {
+                    parser.position += 1;
+                    return Ok(());
+                }
+                _ => return Err(parser.unexpected()),
+            }
+        }
+    }
+    /// Writes the children of given node and all attached nodes as lines of a tree drawn with box-drawing characters.
+    fn write_tree_unicode(
+        &self,
+        node: &Node<D, K>,
+        prefix: &str,
+        tree: &mut String,
+        visited: &mut [bool],
+    ) where
+        K: Display,
+    {
+        let mut children = self
+            .unvisited_children(node, visited)
+            .into_iter()
+            .peekable();
+        while let Some(child) = children.next() {
+            let is_last = children.peek().is_none();
+            let (connector, indent) = if is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            tree.push_str(&format!("{}{}{}\n", prefix, connector, child.key));
+            self.write_tree_unicode(child, &format!("{}{}", prefix, indent), tree, visited);
+        }
+    }
+    /// Returns the children of given node with their heights, the tallest first. Children with the same height keep their order.
+    fn tallest_children<'a>(
+        &'a self,
+        node: &'a Node<D, K>,
+        heights: &[Option<usize>],
+    ) -> Vec<(usize, &'a Node<D, K>)> {
+        let mut children: Vec<(usize, &Node<D, K>)> = node
+            .children
+            .iter()
+            .filter_map(|child| find_position_with_key(&self.nodes, child))
+            .filter_map(|index| Some((heights[index]?, &self.nodes[index])))
+            .collect();
+        children.sort_by_key(|(height, _)| Reverse(*height));
+        children
+    }
+    /// Returns the deepest node below given node, following the first tallest child at every step.
+    fn deepest_below<'a>(
+        &'a self,
+        node: &'a Node<D, K>,
+        heights: &[Option<usize>],
+    ) -> &'a Node<D, K> {
+        let mut deepest = node;
+        for _ in 0..self.nodes.len() {
+            match self.tallest_children(deepest, heights).first() {
+                Some(&(_, child)) => deepest = child,
+                None => break,
+            }
+        }
+        deepest
+    }
+    /// Returns the Mermaid id of every node indexed by position. Plain identifiers other than Mermaid keywords are used as their own id, all other keys get an id from their position which is extended until it differs from every other id.
+    fn mermaid_ids(&self) -> Vec<String>
+    where
+        K: Display,
+    {
+        let is_plain = |key: &str| {
+            !key.is_empty()
+                && !MERMAID_KEYWORDS.contains(&key)
+                && key
+                    .chars()
+                    .all(|character| character.is_ascii_alphanumeric() || character == '_')
+        };
+        let keys: Vec<String> = self.nodes.iter().map(|node| node.key.to_string()).collect();
+        let mut used: HashSet<String> = keys.iter().filter(|key| is_plain(key)).cloned().collect();
+        keys.into_iter()
+            .enumerate()
+            .map(|(index, key)| {
+                if is_plain(&key) {
+                    return key;
+                }
+                let mut id = format!("node{}", index);
+                while !used.insert(id.clone()) {
+                    id.push('_');
+                }
+                id
+            })
+            .collect()
+    }
+}
+/// Deletes given node from given Vector.
+///
+/// # Arguments
+///
+/// * `key` - Key of the node to delete
+/// * `nodes` - The vector where the node should be deleted from.
+///
+fn delete_node<'a, D, K: PartialEq + Clone + Debug>(nodes: &'a mut Vec<Node<D, K>>, key: &'a K) {
+    if let Some(index) = find_position_with_key(nodes, key) {
+        nodes.remove(index);
+    }
+}
+/// Returns the amount of equal keys at the start of both paths.
+fn shared_prefix_len<K: PartialEq>(path_a: &[K], path_b: &[K]) -> usize {
+    path_a
+        .iter()
+        .zip(path_b.iter())
+        .take_while(|(key_a, key_b)| key_a == key_b)
+        .count()
+}
+/// Returns the index of the node with given key in given slice.
+fn find_position_with_key<D, K: PartialEq + Clone + Debug>(
+    nodes: &[Node<D, K>],
+    key: &K,
+) -> Option<usize> {
+    nodes.iter().position(|x| &x.key == key)
+}
+/// Returns all attached keys of given key.
+///
+/// # Arguments
+///
+/// * `key` - Key which childs should be found
+///
+fn find_all_child_nodes<'a, D, K: PartialEq + Clone + Debug>(
+    nodes: &'a Vec<Node<D, K>>,
+    key: &'a K,
+) -> Vec<K> {
+    let mut all_nodes = vec![];
+    if let Some(node_to_delete) = find_node_with_key(nodes, key) {
+        for child in node_to_delete.children.iter() {
+            let mut found_childs = find_all_child_nodes(nodes, child);
+
+            all_nodes.append(&mut found_childs);
+
+            all_nodes.push(child.clone());
+        }
+    }
+    all_nodes
+}
+fn find_node_with_key<'a, D, K: PartialEq + Clone + Debug>(
+    nodes: &'a [Node<D, K>],
+    key: &K,
+) -> Option<&'a Node<D, K>> {
+    nodes.iter().find(|node| &node.key == key)
+}
+/// A Node for the graph structure.
+#[derive(Clone)]
+pub struct Node<D, K: PartialEq + Clone + Debug> {
+    /// The data the node holds.
+    pub data: D,
+    /// All the keys of the children nodes.
+    children: Vec<K>,
+    /// The key of the father of the node.
+    father_key: Option<K>,
+    /// Key
+    key: K,
+    /// The tags the node is labeled with.
+    tags: HashSet<String>,
+    /// True if the node can't get any children.
+    terminal: bool,
+}
+impl<D, K> Node<D, K>
+where
+    K: PartialEq + Clone + Debug,
+{
+    /// Returns a new Node without children. Fathers key has to be empty only for the first node of the graph. All other nodes need a valid fathers key.
+    pub fn new<T: Into<Option<K>>>(data: D, key: K, father_key: T) -> Self {
+        Node {
+            data,
+            key,
+            father_key: father_key.into(),
+            children: vec![],
+            tags: HashSet::new(),
+            terminal: false,
+        }
+    }
+    /// Returns the amount of children of the node.
+    pub fn child_count(&self) -> usize {
+        self.children.len()
+    }
+    /// Returns true if the node holds a childs key of given type.
+    fn has_child(&self, key: &K) -> bool {
+        for child_key in self.children.iter() {
+            if child_key == key {
+                return true;
+            }
+        }
+        false
+    }
+}
+impl<D, K> Default for Graph<D, K>
+where
+    K: PartialEq + Clone + Debug,
+{
+    fn default() -> Self {
+        Graph::new()
+    }
+}
+/// The order in which the nodes of a graph are visited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalOrder {
+    /// Every father is visited before its children.
+    PreOrder,
+    /// Every father is visited after its children.
+    PostOrder,
+    /// The nodes are visited level by level, starting with the root.
+    LevelOrder,
+}
+/// The error returned if a subtree couldn't be replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplaceError<K> {
+    /// No node with given key exists.
+    KeyNotFound(K),
+    /// The replacement has no root.
+    EmptyReplacement,
+    /// A key of the replacement already exists outside the replaced subtree.
+    KeyCollision(K),
+}
+impl<K: Debug> std::fmt::Display for ReplaceError<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplaceError::KeyNotFound(key) => write!(f, "No node with key {:?} found.", key),
+            ReplaceError::EmptyReplacement => write!(f, "The replacement has no root."),
+            ReplaceError::KeyCollision(key) => write!(f, "The key {:?} already exists.", key),
+        }
+    }
+}
+impl<K: Debug> std::error::Error for ReplaceError<K> {}
+/// Two nodes of a graph, for example the ends of a path.
+pub type NodePair<'a, D, K> = (&'a Node<D, K>, &'a Node<D, K>);
+/// An opaque copy of a graph returned by `Graph::snapshot`.
+pub struct GraphSnapshot<D, K: PartialEq + Clone + Debug> {
+    nodes: Vec<Node<D, K>>,
+    max_children: Option<usize>,
+}
+/// A graph which keeps every key only once in a lookup table and refers to it by id internally. Useful for graphs with many long repeated keys.
+pub struct InternedGraph<D, K: Hash + Eq + Clone + Debug> {
+    graph: Graph<D, usize>,
+    ids: HashMap<K, usize>,
+    /// The id the next new key gets. Ids of pruned keys are never reused.
+    next_id: usize,
+}
+impl<D, K> InternedGraph<D, K>
+where
+    K: Hash + Eq + Clone + Debug,
+{
+    /// Returns a empty interned graph.
+    pub fn new() -> Self {
+        InternedGraph {
+            graph: Graph::new(),
+            ids: HashMap::new(),
+            next_id: 0,
+        }
+    }
+    /// Appends a node to graph. Follows the same rules and panics as `Graph::append_node`.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to append.
+    ///
+    pub fn append_node(&mut self, node: Node<D, K>) {
+        let interned_node = Node {
+            key: self.intern(node.key),
+            father_key: node.father_key.map(|key| self.intern(key)),
+            children: node
+                .children
+                .into_iter()
+                .map(|key| self.intern(key))
+                .collect(),
+            data: node.data,
+            tags: node.tags,
+            terminal: node.terminal,
+        };
+        self.graph.append_node(interned_node);
+    }
+    /// Travels the graph with given path and returns the data of the node if one is found. See `Graph::travel_to_node`. Unlike there only the data is returned, as the nodes hold interned ids instead of keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - A slice of keys. Will return the data of the last key of the route if found.
+    ///
+    pub fn travel_to_node(&self, route: &[K]) -> Option<&D> {
+        let route: Option<Vec<usize>> =
+            route.iter().map(|key| self.ids.get(key).copied()).collect();
+        self.graph.travel_to_node(&route?).map(|node| &node.data)
+    }
+    /// Removes the node with given key and all attached nodes. The keys of the removed nodes are dropped from the interned keys and from the children of the remaining nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the node to remove.
+    ///
+    pub fn remove_node_with_childs(&mut self, key: K) {
+        if let Some(id) = self.ids.get(&key) {
+            self.graph.remove_node_with_childs(*id);
+            let remaining: HashSet<usize> = self.graph.nodes.iter().map(|node| node.key).collect();
+            for node in self.graph.nodes.iter_mut() {
+                node.children.retain(|child| remaining.contains(child));
+            }
+            self.ids.retain(|_, id| remaining.contains(id));
+        }
+    }
+    /// Returns the amount of nodes in the graph.
+    pub fn len(&self) -> usize {
+        self.graph.len()
+    }
+    /// Returns if the graph is empty.
+    pub fn is_empty(&self) -> bool {
+        self.graph.is_empty()
+    }
+    /// Returns the amount of unique interned keys and the amount of key references held by the nodes.
+    pub fn intern_stats(&self) -> (usize, usize) {
+        let references = self
+            .graph
+            .nodes
+            .iter()
+            .map(|node| 1 + usize::from(node.father_key.is_some()) + node.children.len())
+            .sum();
+        (self.ids.len(), references)
+    }
+    /// Returns the id of given key and stores it if it is new.
+    fn intern(&mut self, key: K) -> usize {
+        if let Some(id) = self.ids.get(&key) {
+            return *id;
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.ids.insert(key, id);
+        id
+    }
+}
+impl<D, K> Default for InternedGraph<D, K>
+where
+    K: Hash + Eq + Clone + Debug,
+{
+    fn default() -> Self {
+        InternedGraph::new()
+    }
+}
+/// An overview of the size and shape of a graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphStats {
+    /// The amount of nodes in the graph.
+    pub node_count: usize,
+    /// The amount of nodes without children.
+    pub leaf_count: usize,
+    /// The largest distance of a node to the root. 0 if the graph is no tree.
+    pub max_depth: usize,
+    /// The largest amount of children of a single node.
+    pub max_branching_factor: usize,
+    /// Whether the graph is a single valid tree.
+    pub is_tree: bool,
+}
+/// Returns given text as quoted JSON string.
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            character if (character as u32) < 0x20 => {
+                escaped.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => escaped.push(character),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+/// The error returned if a graph couldn't be imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportError {
+    /// The input is no valid JSON. Holds the byte position of the error.
+    InvalidJson(usize),
+    /// A required field is missing.
+    MissingField(&'static str),
+    /// A field has the wrong type.
+    InvalidField(&'static str),
+    /// A key couldn't be parsed.
+    InvalidKey(String),
+    /// The data of a node couldn't be parsed.
+    InvalidData(String),
+    /// A key appears more than once.
+    DuplicateKey(String),
+    /// The document has a version this crate can't read.
+    UnsupportedVersion(String),
+    /// The input couldn't be read. Holds the message of the underlying error.
+    Io(String),
+    /// A line is no edge record or holds an unknown escape sequence. Holds the line number starting at 1.
+    InvalidLine(usize),
+    /// The edges don't form a single tree with one root.
+    InvalidRoot,
+}
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportError::InvalidJson(position) => {
+                write!(f, "Invalid JSON at position {}.", position)
+            }
+            ImportError::MissingField(field) => write!(f, "Missing field {:?}.", field),
+            ImportError::InvalidField(field) => write!(f, "Field {:?} has the wrong type.", field),
+            ImportError::InvalidKey(key) => write!(f, "Couldn't parse key {:?}.", key),
+            ImportError::InvalidData(data) => write!(f, "Couldn't parse data {:?}.", data),
+            ImportError::DuplicateKey(key) => {
+                write!(f, "The key {:?} appears more than once.", key)
+            }
+            ImportError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported document version {}.", version)
+            }
+            ImportError::Io(message) => write!(f, "Couldn't read the input: {}", message),
+            ImportError::InvalidLine(line) => write!(f, "Line {} is no edge record.", line),
+            ImportError::InvalidRoot => write!(f, "The edges don't form a single rooted tree."),
+        }
+    }
+}
+impl std::error::Error for ImportError {}
+/// A parsed JSON value. Booleans are only validated as the import doesn't need them.
+enum JsonValue {
+    Null,
+    Bool,
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+impl JsonValue {
+    /// Returns the field with given name if the value is an object holding it.
+    fn field(&self, name: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields
+                .iter()
+                .find(|(field_name, _)| field_name == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+    /// Returns the string field with given name.
+    fn string_field(&self, name: &'static str) -> Result<&str, ImportError> {
+        match self.field(name) {
+            Some(JsonValue::String(value)) => Ok(value),
+            Some(_) => Err(ImportError::InvalidField(name)),
+            None => Err(ImportError::MissingField(name)),
+        }
+    }
+}
+/// A minimal JSON parser for the graph import.
+struct JsonParser<'a> {
+    text: &'a str,
+    position: usize,
+}
+impl<'a> JsonParser<'a> {
+    /// Parses given text as a single JSON value.
+    fn parse(text: &'a str) -> Result<JsonValue, ImportError> {
+        let mut parser = JsonParser { text, position: 0 };
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.position != text.len() {
+            return Err(parser.error());
+        }
+        Ok(value)
+    }
+    fn error(&self) -> ImportError {
+        ImportError::InvalidJson(self.position)
+    }
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+    fn next_char(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.position += character.len_utf8();
+        Some(character)
+    }
+    fn expect(&mut self, expected: char) -> Result<(), ImportError> {
+        match self.next_char() {
+            Some(character) if character == expected => Ok(()),
+            _ => Err(self.error()),
+        }
+    }
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\n' | '\r' | '\t') = self.peek() {
+            self.position += 1;
+        }
+    }
+    fn parse_value(&mut self) -> Result<JsonValue, ImportError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => self.parse_string().map(JsonValue::String),
+            Some('t') => self.parse_literal("true", JsonValue::Bool),
+            Some('f') => self.parse_literal("false", JsonValue::Bool),
+            Some('n') => self.parse_literal("null", JsonValue::Null),
+            Some('-' | '0'..='9') => self.parse_number(),
+            _ => Err(self.error()),
+        }
+    }
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, ImportError> {
+        if self.text[self.position..].starts_with(literal) {
+            self.position += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error())
+        }
+    }
+    fn parse_number(&mut self) -> Result<JsonValue, ImportError> {
+        let start = self.position;
+        while let Some('-' | '+' | '.' | 'e' | 'E' | '0'..='9') = self.peek() {
+            self.position += 1;
+        }
+        let number = &self.text[start..self.position];
+        if number.parse::<f64>().is_err() {
+            return Err(ImportError::InvalidJson(start));
+        }
+        Ok(JsonValue::Number(number.to_string()))
+    }
+    fn parse_string(&mut self) -> Result<String, ImportError> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            match self.next_char().ok_or_else(|| self.error())? {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escaped = match self.next_char().ok_or_else(|| self.error())? {
+                        '"' => '"',
+                        '\\' => '\\',
+                        '/' => '/',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => self.parse_unicode_escape()?,
+                        _ => return Err(self.error()),
+                    };
+                    string.push(escaped);
+                }
+                character => string.push(character),
+            }
+        }
+    }
+    fn parse_unicode_escape(&mut self) -> Result<char, ImportError> {
+        let high = self.parse_hex()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            self.expect('\\')?;
+            self.expect('u')?;
+            let low = self.parse_hex()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error());
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error())
+    }
+    fn parse_hex(&mut self) -> Result<u32, ImportError> {
+        let hex = self
+            .text
+            .get(self.position..self.position + 4)
+            .ok_or_else(|| self.error())?;
+        let value = u32::from_str_radix(hex, 16).map_err(|_| self.error())?;
+        self.position += 4;
+        Ok(value)
+    }
+    fn parse_array(&mut self) -> Result<JsonValue, ImportError> {
+        self.expect('[')?;
+        let mut values = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(JsonValue::Array(values));
+        }
+        loop {
+            values.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.next_char() {
+                Some(',') => {}
+                Some(']') => return Ok(JsonValue::Array(values)),
+                _ => return Err(self.error()),
+            }
+        }
+    }
+    fn parse_object(&mut self) -> Result<JsonValue, ImportError> {
+        self.expect('{')?;
+        let mut fields = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(JsonValue::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let name = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            fields.push((name, self.parse_value()?));
+            self.skip_whitespace();
+            match self.next_char() {
+                Some(',') => {}
+                Some('}') => return Ok(JsonValue::Object(fields)),
+                _ => return Err(self.error()),
+            }
+        }
+    }
+}
+/// The error returned if a node couldn't be appended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppendError<K> {
+    /// The first node of the graph has a fathers key. Holds the key of the node.
+    RootWithFather(K),
+    /// A node other than the first has no fathers key. Holds the key of the node.
+    MissingFather(K),
+    /// The father of the node doesn't exist. Holds the fathers key.
+    FatherNotFound(K),
+    /// The father already has the maximum amount of children. Holds the fathers key.
+    TooManyChildren(K),
+    /// The father is marked as terminal. Holds the fathers key.
+    TerminalFather(K),
+}
+impl<K: Debug> std::fmt::Display for AppendError<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppendError::RootWithFather(key) => {
+                write!(f, "First node {:?} cant have a fathers key.", key)
+            }
+            AppendError::MissingFather(key) => write!(f, "Node {:?} needs a father key.", key),
+            AppendError::FatherNotFound(key) => write!(f, "No father with key {:?} found.", key),
+            AppendError::TooManyChildren(key) => {
+                write!(f, "Father {:?} cant get more children.", key)
+            }
+            AppendError::TerminalFather(key) => {
+                write!(f, "Father {:?} is terminal and cant get children.", key)
+            }
+        }
+    }
+}
+impl<K: Debug> std::error::Error for AppendError<K> {}
+/// The error returned if no node with the key exists. Holds the key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyNotFound<K>(pub K);
+impl<K: Debug> std::fmt::Display for KeyNotFound<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "No node with key {:?} found.", self.0)
+    }
+}
+impl<K: Debug> std::error::Error for KeyNotFound<K> {}
+/// The version of the format written by `Graph::to_versioned_json`.
+const CURRENT_VERSION: u32 = 1;
+/// Returns given text as quoted DOT identifier.
+fn escape_dot(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+/// The words Mermaid reads as statements or keywords in a flowchart, which can't stand alone as node ids.
+const MERMAID_KEYWORDS: [&str; 17] = [
+    "accDescr",
+    "accTitle",
+    "call",
+    "callback",
+    "class",
+    "classDef",
+    "click",
+    "default",
+    "direction",
+    "end",
+    "flowchart",
+    "graph",
+    "href",
+    "interpolate",
+    "linkStyle",
+    "style",
+    "subgraph",
+];
+/// Returns the text as quoted Mermaid label with quotes and entity characters replaced by entity codes.
+fn escape_mermaid(text: &str) -> String {
+    format!("\"{}\"", text.replace('#', "#35;").replace('"', "#quot;"))
+}
+/// Returns given key as S-expression atom, quoted if it is empty or holds whitespace, parentheses, quotes or backslashes.
+fn escape_sexpr(text: &str) -> String {
+    let needs_quotes = text.is_empty()
+        || text.chars().any(|character| {
+            character.is_whitespace() || matches!(character, '(' | ')' | '"' | '\\')
+        });
+    if needs_quotes {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        text.to_string()
+    }
+}
+/// Returns given key as field of an edge record with backslashes, tabs and line breaks escaped. The empty key is written as `\0`.
+fn escape_edge_key(text: &str) -> String {
+    if text.is_empty() {
+        return "\\0".to_string();
+    }
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+/// Returns the key held by given field of an edge record as written by `escape_edge_key`. Returns None if the field has an unknown escape sequence.
+fn unescape_edge_key(field: &str) -> Option<String> {
+    if field == "\\0" {
+        return Some(String::new());
+    }
+    let mut key = String::with_capacity(field.len());
+    let mut characters = field.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            key.push(character);
+            continue;
+        }
+        match characters.next()? {
+            '\\' => key.push('\\'),
+            't' => key.push('\t'),
+            'n' => key.push('\n'),
+            'r' => key.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(key)
+}
+/// A FNV-1a hasher which, unlike the standard hasher, gives the same result in every run.
+struct StableHasher(u64);
+impl StableHasher {
+    fn new() -> Self {
+        StableHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+/// The error returned if a node couldn't be moved to another father.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReparentError<K> {
+    /// No node with the key of the moved node exists.
+    KeyNotFound(K),
+    /// No node with the key of the new father exists.
+    FatherNotFound(K),
+    /// The root can't get a father.
+    RootMove(K),
+    /// The new father is the node itself or one of its descendants.
+    Cycle {
+        /// The key of the moved node.
+        key: K,
+        /// The key of the new father.
+        new_father: K,
+    },
+}
+impl<K: Debug> std::fmt::Display for ReparentError<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReparentError::KeyNotFound(key) => write!(f, "No node with key {:?} found.", key),
+            ReparentError::FatherNotFound(key) => write!(f, "No father with key {:?} found.", key),
+            ReparentError::RootMove(key) => write!(f, "The root {:?} cant get a father.", key),
+            ReparentError::Cycle { key, new_father } => write!(
+                f,
+                "Moving {:?} below {:?} would create a cycle.",
+                key, new_father
+            ),
+        }
+    }
+}
+impl<K: Debug> std::error::Error for ReparentError<K> {}
+/// The error returned if a subtree couldn't be duplicated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DuplicateError<K> {
+    /// No node with the key of the subtree to copy exists.
+    SourceNotFound(K),
+    /// No node with the key of the new father exists.
+    FatherNotFound(K),
+    /// A renamed key already exists.
+    KeyCollision(K),
+}
+impl<K: Debug> std::fmt::Display for DuplicateError<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DuplicateError::SourceNotFound(key) => write!(f, "No node with key {:?} found.", key),
+            DuplicateError::FatherNotFound(key) => write!(f, "No father with key {:?} found.", key),
+            DuplicateError::KeyCollision(key) => write!(f, "The key {:?} already exists.", key),
+        }
+    }
+}
+impl<K: Debug> std::error::Error for DuplicateError<K> {}
+/// The error returned if the root couldn't be replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetRootError<K> {
+    /// The graph has no root to replace.
+    EmptyGraph,
+    /// The new root has a fathers key. Holds the key of the new root.
+    RootWithFather(K),
+    /// The key of the new root already exists.
+    KeyCollision(K),
+}
+impl<K: Debug> std::fmt::Display for SetRootError<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetRootError::EmptyGraph => write!(f, "The graph has no root."),
+            SetRootError::RootWithFather(key) => {
+                write!(f, "Root {:?} cant have a fathers key.", key)
+            }
+            SetRootError::KeyCollision(key) => write!(f, "The key {:?} already exists.", key),
+        }
+    }
+}
+impl<K: Debug> std::error::Error for SetRootError<K> {}
+/// The error returned if two graphs couldn't be merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeError<K> {
+    /// The other graph has a different root. Holds the key of its root.
+    RootMismatch(K),
+    /// A key is in both graphs with different fathers.
+    KeyConflict(K),
+}
+impl<K: Debug> std::fmt::Display for MergeError<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeError::RootMismatch(key) => write!(f, "The root {:?} differs.", key),
+            MergeError::KeyConflict(key) => {
+                write!(f, "The key {:?} has different fathers.", key)
+            }
+        }
+    }
+}
+impl<K: Debug> std::error::Error for MergeError<K> {}
+/// The error returned if a graph couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input ended before the last node was closed.
+    UnexpectedEnd,
+    /// An unexpected character was found. Holds its byte position.
+    UnexpectedCharacter(usize),
+    /// A key couldn't be parsed.
+    InvalidKey(String),
+    /// A key appears more than once.
+    DuplicateKey(String),
+}
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd => write!(f, "Unexpected end of input."),
+            ParseError::UnexpectedCharacter(position) => {
+                write!(f, "Unexpected character at position {}.", position)
+            }
+            ParseError::InvalidKey(key) => write!(f, "Couldn't parse key {:?}.", key),
+            ParseError::DuplicateKey(key) => {
+                write!(f, "The key {:?} appears more than once.", key)
+            }
+        }
+    }
+}
+impl std::error::Error for ParseError {}
+/// A minimal parser for S-expressions of keys.
+struct SexprParser<'a> {
+    text: &'a str,
+    position: usize,
+}
+impl<'a> SexprParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+    fn unexpected(&self) -> ParseError {
+        match self.peek() {
+            Some(_) => ParseError::UnexpectedCharacter(self.position),
+            None => ParseError::UnexpectedEnd,
+        }
+    }
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        if self.peek() != Some(expected) {
+            return Err(self.unexpected());
+        }
+        self.position += expected.len_utf8();
+        Ok(())
+    }
+    fn skip_whitespace(&mut self) {
+        while let Some(character) = self.peek().filter(|character| character.is_whitespace()) {
+            self.position += character.len_utf8();
+        }
+    }
+    /// Returns the text up to the next whitespace or parenthesis.
+    fn atom(&mut self) -> &'a str {
+        let start = self.position;
+        while let Some(character) = self
+            .peek()
+            .filter(|character| !character.is_whitespace() && !matches!(character, '(' | ')'))
+        {
+            self.position += character.len_utf8();
+        }
+        &self.text[start..self.position]
+    }
+    /// Returns the next key, either a quoted string with escaped quotes and backslashes or a plain atom.
+    fn key(&mut self) -> Result<String, ParseError> {
+        if self.peek() != Some('"') {
+            let atom = self.atom();
+            if atom.is_empty() {
+                return Err(self.unexpected());
+            }
+            return Ok(atom.to_string());
+        }
+        self.position += 1;
+        let mut key = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.position += 1;
+                    return Ok(key);
+                }
+                Some('\\') => {
+                    self.position += 1;
+                    match self.peek() {
+                        Some(character @ ('"' | '\\')) => {
+                            key.push(character);
+                            self.position += 1;
+                        }
+                        _ => return Err(self.unexpected()),
+                    }
+                }
+                Some(character) => {
+                    key.push(character);
+                    self.position += character.len_utf8();
+                }
+                None => return Err(ParseError::UnexpectedEnd),
+            }
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AppendError, DuplicateError, Graph, GraphStats, ImportError, InternedGraph, KeyNotFound,
+        MergeError, Node, ParseError, ReparentError, ReplaceError, SetRootError, TraversalOrder,
+    };
+
+    fn sample_graph() -> Graph<&'static str, &'static str> {
+        let mut graph = Graph::new();
+        graph.append_node(Node::new(
+            "Hallo, willst du etwas Essen gehen, oder einen Sitzplatz buchen?",
+            "Start",
+            None,
+        ));
+        graph.append_node(Node::new(
+            "Ok, was willst du essen? Pizza oder Pasta?",
+            "Essen",
+            "Start",
+        ));
+        graph.append_node(Node::new(
+            "Ok, willst du am Fenster oder am Gang sitzen?",
+            "Sitzplatz",
+            "Start",
+        ));
+        graph.append_node(Node::new(
+            "Ok, dann einen Sitzlatz am Gang. Bis dann!",
+            "Gang",
+            "Sitzplatz",
+        ));
+        graph
+    }
+
+    fn keys<'a>(nodes: &[&'a Node<&'static str, &'static str>]) -> Vec<&'a str> {
+        nodes.iter().map(|node| node.key).collect()
+    }
+
+    #[test]
+    fn it_works() {
+        let first_node = Node::new(
+            "Hallo, willst du etwas Essen gehen, oder einen Sitzplatz buchen?",
+            "Start",
+            None,
+        );
+        let second_node = Node::new(
+            "Ok, was willst du essen? Pizza oder Pasta?",
+            "Essen",
+            "Start",
+        );
+        let third_node = Node::new(
+            "Ok, willst du am Fenster oder am Gang sitzen?",
+            "Sitzplatz",
+            "Start",
+        );
+        let fourth_node = Node::new(
+            "Ok, dann einen Sitzlatz am Gang. Bis dann!",
+            "Gang",
+            "Sitzplatz",
+        );
+
+        let nodes = vec![first_node, second_node, third_node, fourth_node];
+        let mut graph = Graph::new();
+
+        for node in nodes.into_iter() {
+            graph.append_node(node);
+        }
+        assert_eq!(graph.len(), 4);
+        let node = graph.travel_to_node(&["Sitzplatz", "Gang"]);
+        assert_eq!(
+            node.unwrap().data,
+            "Ok, dann einen Sitzlatz am Gang. Bis dann!"
+        );
+
+        graph.remove_node_with_childs("Sitzplatz");
+
+        assert_eq!(graph.len(), 2);
+    }
+
+    #[test]
+    fn nodes_at_depth_returns_row() {
+        let graph = sample_graph();
+        assert_eq!(keys(&graph.nodes_at_depth(0)), ["Start"]);
+        assert_eq!(keys(&graph.nodes_at_depth(1)), ["Essen", "Sitzplatz"]);
+        assert_eq!(keys(&graph.nodes_at_depth(2)), ["Gang"]);
+        assert!(graph.nodes_at_depth(3).is_empty());
+    }
+
+    #[test]
+    fn structurally_eq_ignores_data() {
+        let graph = sample_graph();
+        let mut other = graph.map_data(|data| data.len());
+        assert!(graph.structurally_eq(&other));
+
+        other.remove_node_with_childs("Gang");
+        assert!(!graph.structurally_eq(&other));
+    }
+
+    #[test]
+    fn leaf_data_returns_endpoints() {
+        let graph = sample_graph();
+        assert_eq!(
+            graph.leaf_data(),
+            [
+                &"Ok, was willst du essen? Pizza oder Pasta?",
+                &"Ok, dann einen Sitzlatz am Gang. Bis dann!"
+            ]
+        );
+    }
+
+    #[test]
+    fn iter_order_visits_in_given_order() {
+        let graph = sample_graph();
+        let order = |order| {
+            graph
+                .iter_order(order)
+                .map(|node| node.key)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            order(TraversalOrder::PreOrder),
+            ["Start", "Essen", "Sitzplatz", "Gang"]
+        );
+        assert_eq!(
+            order(TraversalOrder::PostOrder),
+            ["Essen", "Gang", "Sitzplatz", "Start"]
+        );
+        assert_eq!(
+            order(TraversalOrder::LevelOrder),
+            ["Start", "Essen", "Sitzplatz", "Gang"]
+        );
+    }
+
+    #[test]
+    fn replace_subtree_round_trip() {
+        let mut graph = sample_graph();
+        let mut replacement = Graph::new();
+        replacement.append_node(Node::new("Tisch?", "Tisch", None));
+        replacement.append_node(Node::new("Am Fenster.", "Fenster", "Tisch"));
+
+        let removed = graph.replace_subtree(&"Sitzplatz", replacement).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(graph.len(), 4);
+        assert_eq!(
+            graph.travel_to_node(&["Tisch", "Fenster"]).unwrap().data,
+            "Am Fenster."
+        );
+        assert!(graph.travel_to_node(&["Sitzplatz"]).is_none());
+
+        graph.replace_subtree(&"Tisch", removed).unwrap();
+        assert!(graph.structurally_eq(&sample_graph()));
+    }
+
+    #[test]
+    fn replace_subtree_rejects_collisions() {
+        let mut graph = sample_graph();
+        let mut replacement = Graph::new();
+        replacement.append_node(Node::new("Essen?", "Essen", None));
+
+        assert_eq!(
+            graph.replace_subtree(&"Sitzplatz", replacement).err(),
+            Some(ReplaceError::KeyCollision("Essen"))
+        );
+        assert!(graph.structurally_eq(&sample_graph()));
+    }
+
+    #[test]
+    fn iter_order_size_hint_matches_len() {
+        let graph = sample_graph();
+        for order in [
+            TraversalOrder::PreOrder,
+            TraversalOrder::PostOrder,
+            TraversalOrder::LevelOrder,
+        ] {
+            let mut iter = graph.iter_order(order);
+            assert_eq!(iter.size_hint(), (graph.len(), Some(graph.len())));
+            iter.next();
+            assert_eq!(iter.len(), graph.len() - 1);
+        }
+    }
+
+    #[test]
+    fn path_between_goes_through_common_ancestor() {
+        let graph = sample_graph();
+        assert_eq!(
+            graph.path_between(&"Essen", &"Gang"),
+            Some(vec!["Essen", "Start", "Sitzplatz", "Gang"])
+        );
+        assert_eq!(
+            graph.path_between(&"Gang", &"Sitzplatz"),
+            Some(vec!["Gang", "Sitzplatz"])
+        );
+        assert_eq!(graph.path_between(&"Gang", &"Gang"), Some(vec!["Gang"]));
+        assert_eq!(graph.path_between(&"Essen", &"Fenster"), None);
+    }
+
+    #[test]
+    fn count_matching_counts_leaves() {
+        let graph = sample_graph();
+        assert_eq!(graph.count_matching(|node| node.children.is_empty()), 2);
+        assert_eq!(graph.count_matching(|_| false), 0);
+    }
+
+    #[test]
+    fn interned_graph_travels_like_graph() {
+        let mut graph = InternedGraph::new();
+        graph.append_node(Node::new("Hallo", "Start".to_string(), None));
+        graph.append_node(Node::new("Essen", "Essen".to_string(), "Start".to_string()));
+        graph.append_node(Node::new(
+            "Sitzplatz",
+            "Sitzplatz".to_string(),
+            "Start".to_string(),
+        ));
+        graph.append_node(Node::new(
+            "Gang",
+            "Gang".to_string(),
+            "Sitzplatz".to_string(),
+        ));
+
+        assert_eq!(graph.len(), 4);
+        assert_eq!(
+            graph.travel_to_node(&["Sitzplatz".to_string(), "Gang".to_string()]),
+            Some(&"Gang")
+        );
+        assert!(graph.travel_to_node(&["Gang".to_string()]).is_none());
+        assert!(graph.travel_to_node(&["Fenster".to_string()]).is_none());
+        assert_eq!(graph.intern_stats(), (4, 10));
+
+        graph.remove_node_with_childs("Sitzplatz".to_string());
+        assert_eq!(graph.len(), 2);
+        assert_eq!(graph.intern_stats(), (2, 4));
+        graph.append_node(Node::new(
+            "Fenster",
+            "Fenster".to_string(),
+            "Start".to_string(),
+        ));
+        assert_eq!(graph.intern_stats(), (3, 7));
+        assert_eq!(
+            graph.travel_to_node(&["Fenster".to_string()]),
+            Some(&"Fenster")
+        );
+        assert_eq!(graph.travel_to_node(&["Essen".to_string()]), Some(&"Essen"));
+    }
+
+    #[test]
+    fn map_keys_prefixes_all_keys() {
+        let graph = sample_graph().map_keys(|key| format!("a_{}", key));
+        assert_eq!(graph.len(), 4);
+        assert_eq!(
+            graph
+                .travel_to_node(&["a_Sitzplatz".to_string(), "a_Gang".to_string()])
+                .unwrap()
+                .data,
+            "Ok, dann einen Sitzlatz am Gang. Bis dann!"
+        );
+        assert!(graph.travel_to_node(&["Sitzplatz".to_string()]).is_none());
+    }
+
+    #[test]
+    fn stats_summarizes_sample_graph() {
+        assert_eq!(
+            sample_graph().stats(),
+            GraphStats {
+                node_count: 4,
+                leaf_count: 2,
+                max_depth: 2,
+                max_branching_factor: 2,
+                is_tree: true,
+            }
+        );
+    }
+
+    #[test]
+    fn nodes_with_child_count_finds_decision_points() {
+        let graph = sample_graph();
+        assert_eq!(keys(&graph.nodes_with_child_count(2)), ["Start"]);
+        assert_eq!(keys(&graph.nodes_with_child_count(0)), ["Essen", "Gang"]);
+    }
+
+    #[test]
+    fn append_node_ref_returns_appended_node() {
+        let mut graph = sample_graph();
+        let data = graph
+            .append_node_ref(Node::new("Am Fenster.", "Fenster", "Sitzplatz"))
+            .data;
+        assert_eq!(data, "Am Fenster.");
+        assert_eq!(graph.len(), 5);
+    }
+
+    #[test]
+    fn cheapest_path_to_sums_edge_weights() {
+        let graph = sample_graph();
+        let weight = |father: &&str, child: &&str| match (*father, *child) {
+            ("Start", "Sitzplatz") => 3,
+            ("Sitzplatz", "Gang") => 4,
+            _ => 1,
+        };
+        assert_eq!(
+            graph.cheapest_path_to(&"Gang", weight),
+            Some((vec!["Start", "Sitzplatz", "Gang"], 7))
+        );
+        assert_eq!(
+            graph.cheapest_path_to(&"Start", weight),
+            Some((vec!["Start"], 0))
+        );
+        assert_eq!(graph.cheapest_path_to(&"Fenster", weight), None);
+    }
+
+    #[test]
+    fn to_indexed_round_trips_through_mapping() {
+        let graph = sample_graph();
+        let (indexed, keys) = graph.to_indexed();
+        assert_eq!(keys, ["Start", "Essen", "Sitzplatz", "Gang"]);
+        assert_eq!(
+            indexed.travel_to_node(&[2, 3]).unwrap().data,
+            graph.travel_to_node(&["Sitzplatz", "Gang"]).unwrap().data
+        );
+        assert!(indexed
+            .map_keys(|index| keys[*index])
+            .structurally_eq(&graph));
+    }
+
+    #[test]
+    fn is_tree_accepts_sample_graph() {
+        assert!(sample_graph().is_tree());
+        assert!(!Graph::<&str, &str>::new().is_tree());
+    }
+
+    #[test]
+    fn is_tree_rejects_two_roots() {
+        let mut graph = sample_graph();
+        graph.nodes.push(Node::new("Tschüss", "Ende", None));
+        assert!(!graph.is_tree());
+        assert!(!graph.stats().is_tree);
+    }
+
+    #[test]
+    fn reachable_from_returns_subtree() {
+        let graph = sample_graph();
+        assert_eq!(
+            keys(&graph.reachable_from(&"Sitzplatz")),
+            ["Sitzplatz", "Gang"]
+        );
+        assert!(graph.reachable_from(&"Fenster").is_empty());
+    }
+
+    #[test]
+    fn truncate_depth_removes_deeper_nodes() {
+        let mut graph = sample_graph();
+        graph.truncate_depth(1);
+        assert_eq!(graph.len(), 3);
+        assert!(graph.travel_to_node(&["Sitzplatz", "Gang"]).is_none());
+        assert!(graph
+            .travel_to_node(&["Sitzplatz"])
+            .unwrap()
+            .children
+            .is_empty());
+        assert!(graph.is_tree());
+    }
+
+    #[test]
+    fn dedup_leaves_merges_equal_siblings() {
+        let mut graph = sample_graph();
+        graph.append_node(Node::new("Am Fenster.", "Fenster", "Sitzplatz"));
+        graph.append_node(Node::new("Am Fenster.", "Fenster2", "Sitzplatz"));
+        graph.append_node(Node::new("Am Fenster.", "Fenster3", "Essen"));
+        graph.dedup_leaves();
+        assert_eq!(graph.len(), 6);
+        assert!(graph.travel_to_node(&["Sitzplatz", "Fenster2"]).is_none());
+        assert_eq!(
+            graph.travel_to_node(&["Sitzplatz"]).unwrap().children,
+            ["Gang", "Fenster"]
+        );
+        assert!(graph.travel_to_node(&["Essen", "Fenster3"]).is_some());
+    }
+
+    #[test]
+    fn position_of_returns_insertion_index() {
+        let graph = sample_graph();
+        assert_eq!(graph.position_of(&"Gang"), Some(3));
+        assert_eq!(graph.position_of(&"Fenster"), None);
+    }
+
+    #[test]
+    fn to_nested_json_nests_children() {
+        assert_eq!(
+            sample_graph().to_nested_json(),
+            concat!(
+                r#"{"key":"Start","data":"Hallo, willst du etwas Essen gehen, oder einen Sitzplatz buchen?","children":["#,
+                r#"{"key":"Essen","data":"Ok, was willst du essen? Pizza oder Pasta?","children":[]},"#,
+                r#"{"key":"Sitzplatz","data":"Ok, willst du am Fenster oder am Gang sitzen?","children":["#,
+                r#"{"key":"Gang","data":"Ok, dann einen Sitzlatz am Gang. Bis dann!","children":[]}]}]}"#
+            )
+        );
+        assert_eq!(Graph::<&str, &str>::new().to_nested_json(), "null");
+    }
+
+    #[test]
+    fn from_nested_json_round_trips() {
+        let json = sample_graph().to_nested_json();
+        let graph: Graph<String, String> = Graph::from_nested_json(&json).unwrap();
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.to_nested_json(), json);
+        assert_eq!(
+            graph
+                .travel_to_node(&["Sitzplatz".to_string(), "Gang".to_string()])
+                .unwrap()
+                .data,
+            "Ok, dann einen Sitzlatz am Gang. Bis dann!"
+        );
+    }
+
+    #[test]
+    fn from_nested_json_rejects_invalid_input() {
+        let duplicate = r#"{"key":"Start","data":"a","children":[{"key":"Start","data":"b"}]}"#;
+        assert_eq!(
+            Graph::<String, String>::from_nested_json(duplicate).err(),
+            Some(ImportError::DuplicateKey("Start".to_string()))
+        );
+        assert_eq!(
+            Graph::<String, String>::from_nested_json(r#"{"key":"Start"}"#).err(),
+            Some(ImportError::MissingField("data"))
+        );
+        assert_eq!(
+            Graph::<String, String>::from_nested_json(r#"{"key":"Start""#).err(),
+            Some(ImportError::InvalidJson(14))
+        );
+        assert!(Graph::<String, String>::from_nested_json(" null ")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn busiest_node_returns_most_children() {
+        let mut graph = sample_graph();
+        assert_eq!(graph.busiest_node().unwrap().key, "Start");
+        graph.append_node(Node::new("Am Fenster.", "Fenster", "Sitzplatz"));
+        assert_eq!(graph.busiest_node().unwrap().key, "Start");
+        assert!(Graph::<&str, &str>::new().busiest_node().is_none());
+    }
+
+    #[test]
+    fn try_new_rooted_rejects_root_with_father() {
+        let graph = Graph::try_new_rooted(Node::new("Hallo", "Start", None)).unwrap();
+        assert_eq!(graph.len(), 1);
+        assert!(graph.is_tree());
+        assert_eq!(
+            Graph::try_new_rooted(Node::new("Hallo", "Start", "Vorher")).err(),
+            Some(AppendError::RootWithFather("Start"))
+        );
+    }
+
+    #[test]
+    fn path_nodes_returns_nodes_from_root() {
+        let graph = sample_graph();
+        let data: Vec<_> = graph
+            .path_nodes(&"Gang")
+            .unwrap()
+            .into_iter()
+            .map(|node| node.data)
+            .collect();
+        assert_eq!(
+            data,
+            [
+                "Hallo, willst du etwas Essen gehen, oder einen Sitzplatz buchen?",
+                "Ok, willst du am Fenster oder am Gang sitzen?",
+                "Ok, dann einen Sitzlatz am Gang. Bis dann!"
+            ]
+        );
+        assert!(graph.path_nodes(&"Fenster").is_none());
+    }
+
+    #[test]
+    fn insert_child_at_places_child_in_order() {
+        let mut graph = sample_graph();
+        graph
+            .insert_child_at(&"Start", Node::new("Trinken?", "Trinken", None), 0)
+            .unwrap();
+        graph
+            .insert_child_at(&"Start", Node::new("Tschüss.", "Ende", None), 10)
+            .unwrap();
+        let order: Vec<_> = graph
+            .iter_order(TraversalOrder::LevelOrder)
+            .map(|node| node.key)
+            .collect();
+        assert_eq!(
+            order,
+            ["Start", "Trinken", "Essen", "Sitzplatz", "Ende", "Gang"]
+        );
+        assert!(graph.is_tree());
+        assert_eq!(
+            graph.insert_child_at(&"Fenster", Node::new("", "Tisch", None), 0),
+            Err(AppendError::FatherNotFound("Fenster"))
+        );
+    }
+
+    #[test]
+    fn check_single_parent_reports_shared_children() {
+        let mut graph = sample_graph();
+        assert_eq!(graph.check_single_parent(), Ok(()));
+        graph.nodes[1].children.push("Gang");
+        assert_eq!(graph.check_single_parent(), Err(vec!["Gang"]));
+    }
+
+    #[test]
+    fn tags_can_be_added_queried_and_removed() {
+        let mut graph = sample_graph();
+        graph.add_tag(&"Essen", "ending".to_string());
+        graph.add_tag(&"Gang", "ending".to_string());
+        graph.add_tag(&"Gang", "retry".to_string());
+        graph.add_tag(&"Fenster", "ending".to_string());
+        assert_eq!(keys(&graph.nodes_with_tag("ending")), ["Essen", "Gang"]);
+        assert_eq!(keys(&graph.nodes_with_tag("retry")), ["Gang"]);
+
+        graph.remove_tag(&"Gang", "ending");
+        assert_eq!(keys(&graph.nodes_with_tag("ending")), ["Essen"]);
+        assert!(graph.nodes_with_tag("start").is_empty());
+    }
+
+    #[test]
+    fn total_depth_sums_node_depths() {
+        assert_eq!(sample_graph().total_depth(), 4);
+        assert_eq!(Graph::<&str, &str>::new().total_depth(), 0);
+    }
+
+    #[test]
+    fn is_balanced_compares_child_heights() {
+        let mut graph = sample_graph();
+        assert!(graph.is_balanced());
+        graph.append_node(Node::new("Am Fenster.", "Fenster", "Gang"));
+        assert!(!graph.is_balanced());
+    }
+
+    #[test]
+    fn children_iter_finds_child_by_data() {
+        let graph = sample_graph();
+        let child = graph
+            .children_iter(&"Start")
+            .find(|child| child.data.contains("Fenster"));
+        assert_eq!(child.unwrap().key, "Sitzplatz");
+        assert_eq!(graph.children_iter(&"Gang").count(), 0);
+        assert_eq!(graph.children_iter(&"Fenster").count(), 0);
+    }
+
+    #[test]
+    fn swap_keys_exchanges_identities() {
+        let mut graph = sample_graph();
+        graph.swap_keys(&"Essen", &"Sitzplatz").unwrap();
+        assert_eq!(
+            graph.travel_to_node(&["Essen", "Gang"]).unwrap().data,
+            "Ok, dann einen Sitzlatz am Gang. Bis dann!"
+        );
+        assert_eq!(
+            graph.travel_to_node(&["Sitzplatz"]).unwrap().data,
+            "Ok, was willst du essen? Pizza oder Pasta?"
+        );
+        assert_eq!(graph.nodes[0].children, ["Sitzplatz", "Essen"]);
+        assert_eq!(
+            graph.swap_keys(&"Essen", &"Fenster"),
+            Err(KeyNotFound("Fenster"))
+        );
+    }
+
+    #[test]
+    fn to_parent_array_points_to_father_index() {
+        let (keys, parents) = sample_graph().to_parent_array();
+        assert_eq!(keys, ["Start", "Essen", "Sitzplatz", "Gang"]);
+        assert_eq!(parents, [None, Some(0), Some(0), Some(2)]);
+        assert_eq!(keys[parents[3].unwrap()], "Sitzplatz");
+    }
+
+    #[test]
+    fn append_data_under_generates_distinct_keys() {
+        let mut graph: Graph<&str, usize> = Graph::new();
+        graph.append_node(Node::new("Hallo", 1, None));
+        let first = graph.append_data_under(&1, "Essen").unwrap();
+        let second = graph.append_data_under(&1, "Sitzplatz").unwrap();
+        let third = graph.append_data_under(&second, "Gang").unwrap();
+        assert_eq!((first, second, third), (2, 3, 4));
+        assert_eq!(graph.travel_to_node(&[3, 4]).unwrap().data, "Gang");
+        assert_eq!(
+            graph.append_data_under(&9, "Fenster"),
+            Err(AppendError::FatherNotFound(9))
+        );
+    }
+
+    #[test]
+    fn shared_ancestry_returns_common_prefix() {
+        let graph = sample_graph();
+        assert_eq!(graph.shared_ancestry(&"Essen", &"Gang"), ["Start"]);
+        assert_eq!(
+            graph.shared_ancestry(&"Sitzplatz", &"Gang"),
+            ["Start", "Sitzplatz"]
+        );
+        assert!(graph.shared_ancestry(&"Essen", &"Fenster").is_empty());
+    }
+
+    #[test]
+    fn travel_to_node_returns_none_for_missing_child_node() {
+        let mut graph = sample_graph();
+        graph.nodes.retain(|node| node.key != "Gang");
+        assert_eq!(graph.nodes[2].children, ["Gang"]);
+        assert!(graph.travel_to_node(&["Sitzplatz", "Gang"]).is_none());
+        if std::env::var_os("GRAPH_TRAVEL_CHILD").is_some() {
+            return;
+        }
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "tests::travel_to_node_returns_none_for_missing_child_node",
+                "--nocapture",
+                "--test-threads=1",
+            ])
+            .env("GRAPH_TRAVEL_CHILD", "1")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(output.status.success());
+        assert!(stdout
+            .contains("test tests::travel_to_node_returns_none_for_missing_child_node ... ok\n"));
+        assert!(stdout.lines().all(|line| line.is_empty()
+            || line.starts_with("running ")
+            || line.starts_with("test ")));
+    }
+
+    #[test]
+    fn travel_to_node_rejects_route_starting_at_root() {
+        let graph = sample_graph();
+        assert!(graph.travel_to_node(&["Start"]).is_none());
+        assert!(graph
+            .travel_to_node(&["Start", "Sitzplatz", "Gang"])
+            .is_none());
+        assert_eq!(graph.travel_to_node(&[]).unwrap().key, "Start");
+    }
+
+    #[test]
+    fn paths_of_length_returns_parent_child_pairs() {
+        let graph = sample_graph();
+        assert_eq!(
+            graph.paths_of_length(1),
+            [
+                vec!["Start", "Essen"],
+                vec!["Start", "Sitzplatz"],
+                vec!["Sitzplatz", "Gang"]
+            ]
+        );
+        assert_eq!(
+            graph.paths_of_length(2),
+            [vec!["Start", "Sitzplatz", "Gang"]]
+        );
+        assert!(graph.paths_of_length(3).is_empty());
+    }
+
+    #[test]
+    fn index_path_locates_node_by_positions() {
+        let graph = sample_graph();
+        assert_eq!(graph.index_path(&"Gang"), Some(vec![1, 0]));
+        assert_eq!(graph.index_path(&"Start"), Some(vec![]));
+        assert_eq!(graph.index_path(&"Fenster"), None);
+    }
+
+    #[test]
+    fn node_at_index_path_round_trips_index_path() {
+        let graph = sample_graph();
+        for node in graph.nodes.iter() {
+            let path = graph.index_path(&node.key).unwrap();
+            assert_eq!(graph.node_at_index_path(&path).unwrap().key, node.key);
+        }
+        assert!(graph.node_at_index_path(&[2]).is_none());
+        assert!(graph.node_at_index_path(&[0, 0]).is_none());
+    }
+
+    #[test]
+    fn average_branching_factor_ignores_leaves() {
+        assert_eq!(sample_graph().average_branching_factor(), 1.5);
+        let single = Graph::try_new_rooted(Node::new("Hallo", "Start", None)).unwrap();
+        assert_eq!(single.average_branching_factor(), 0.0);
+    }
+
+    #[test]
+    fn from_versioned_reads_v1_and_unversioned_documents() {
+        let document = concat!(
+            r#"{"version": 1, "root": {"key": "Start", "data": "Hallo", "children": ["#,
+            r#"{"key": "Essen", "data": "Essen?", "children": []}]}}"#
+        );
+        let graph: Graph<String, String> = Graph::from_versioned(document).unwrap();
+        assert_eq!(graph.len(), 2);
+        assert_eq!(
+            graph.travel_to_node(&["Essen".to_string()]).unwrap().data,
+            "Essen?"
+        );
+        assert_eq!(
+            Graph::<String, String>::from_versioned(&graph.to_versioned_json())
+                .unwrap()
+                .to_nested_json(),
+            graph.to_nested_json()
+        );
+
+        let unversioned = sample_graph().to_nested_json();
+        let graph: Graph<String, String> = Graph::from_versioned(&unversioned).unwrap();
+        assert_eq!(graph.len(), 4);
+
+        assert_eq!(
+            Graph::<String, String>::from_versioned(r#"{"version":2,"root":null}"#).err(),
+            Some(ImportError::UnsupportedVersion("2".to_string()))
+        );
+    }
+
+    #[test]
+    fn nodes_in_depth_range_filters_by_depth() {
+        let graph = sample_graph();
+        assert_eq!(
+            keys(&graph.nodes_in_depth_range(1, 2)),
+            ["Essen", "Sitzplatz", "Gang"]
+        );
+        assert_eq!(keys(&graph.nodes_in_depth_range(0, 0)), ["Start"]);
+        assert!(graph.nodes_in_depth_range(3, 5).is_empty());
+    }
+
+    #[test]
+    fn retain_paths_matching_keeps_matching_branches() {
+        let mut graph = sample_graph();
+        graph.append_node(Node::new("Am Fenster.", "Fenster", "Sitzplatz"));
+        graph.retain_paths_matching(|data| data.contains("Gang"));
+        assert_eq!(
+            keys(
+                &graph
+                    .iter_order(TraversalOrder::PreOrder)
+                    .collect::<Vec<_>>()
+            ),
+            ["Start", "Sitzplatz", "Gang", "Fenster"]
+        );
+
+        graph.retain_paths_matching(|data| data.contains("Bis dann"));
+        assert_eq!(
+            keys(
+                &graph
+                    .iter_order(TraversalOrder::PreOrder)
+                    .collect::<Vec<_>>()
+            ),
+            ["Start", "Sitzplatz", "Gang"]
+        );
+        assert!(graph.is_tree());
+
+        graph.retain_paths_matching(|_| false);
+        assert!(graph.is_empty());
+    }
+
+    #[test]
+    fn lookups_agree_with_travel_to_node() {
+        let graph = sample_graph();
+        assert!(graph.contains_key(&"Gang"));
+        assert!(!graph.contains_key(&"Fenster"));
+        assert_eq!(
+            graph.get_node(&"Gang").map(|node| node.data),
+            graph
+                .travel_to_node(&["Sitzplatz", "Gang"])
+                .map(|node| node.data)
+        );
+
+        let mut large = Graph::new();
+        large.append_node(Node::new(0, 0, None));
+        for key in 1..2000 {
+            large.append_node(Node::new(key, key, key - 1));
+        }
+        let route: Vec<usize> = (1..2000).collect();
+        assert_eq!(large.travel_to_node(&route).unwrap().data, 1999);
+        assert_eq!(large.get_node(&1999).unwrap().data, 1999);
+        assert!(!large.contains_key(&2000));
+    }
+
+    #[test]
+    fn collapse_chains_merges_linear_nodes() {
+        let mut graph = Graph::new();
+        graph.append_node(Node::new("a".to_string(), "Start", None));
+        graph.append_node(Node::new("b".to_string(), "Mitte", "Start"));
+        graph.append_node(Node::new("c".to_string(), "Ende", "Mitte"));
+        graph.collapse_chains(|father, child| format!("{}{}", father, child));
+        assert_eq!(graph.len(), 1);
+        assert_eq!(graph.travel_to_node(&[]).unwrap().data, "abc");
+
+        let mut graph = sample_graph();
+        graph.collapse_chains(|father, _| father);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(
+            graph.travel_to_node(&["Sitzplatz"]).unwrap().data,
+            "Ok, willst du am Fenster oder am Gang sitzen?"
+        );
+        assert!(graph
+            .travel_to_node(&["Sitzplatz"])
+            .unwrap()
+            .children
+            .is_empty());
+        assert!(graph.is_tree());
+
+        let mut graph = Graph::new();
+        graph.append_node(Node::new(0, "A", None));
+        graph.append_node(Node::new(1, "A", "A"));
+        graph.collapse_chains(|father, child| father + child);
+        assert_eq!(graph.len(), 2);
+        assert_eq!(graph.travel_to_node(&[]).unwrap().data, 0);
+
+        let mut graph = Graph::new();
+        graph.append_node(Node::new(1, "R", None));
+        graph.append_node(Node::new(2, "C", "R"));
+        graph.nodes[1].children.push("R");
+        graph.collapse_chains(|father, child| father + child);
+        assert_eq!(graph.len(), 1);
+        assert_eq!(graph.travel_to_node(&[]).unwrap().data, 3);
+    }
+
+    #[test]
+    fn to_dot_ranked_groups_levels() {
+        assert_eq!(
+            sample_graph().to_dot_ranked(),
+            concat!(
+                "digraph {\n",
+                "    \"Start\";\n",
+                "    \"Essen\";\n",
+                "    \"Sitzplatz\";\n",
+                "    \"Gang\";\n",
+                "    \"Start\" -> \"Essen\";\n",
+                "    \"Start\" -> \"Sitzplatz\";\n",
+                "    \"Sitzplatz\" -> \"Gang\";\n",
+                "    { rank=same; \"Start\"; }\n",
+                "    { rank=same; \"Essen\"; \"Sitzplatz\"; }\n",
+                "    { rank=same; \"Gang\"; }\n",
+                "}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn lca_many_finds_common_ancestor() {
+        let mut graph = sample_graph();
+        graph.append_node(Node::new("Am Fenster.", "Fenster", "Sitzplatz"));
+        assert_eq!(
+            graph.lca_many(&["Essen", "Gang", "Fenster"]).unwrap().key,
+            "Start"
+        );
+        assert_eq!(
+            graph.lca_many(&["Gang", "Fenster"]).unwrap().key,
+            "Sitzplatz"
+        );
+        assert_eq!(graph.lca_many(&["Gang"]).unwrap().key, "Gang");
+        assert!(graph.lca_many(&[]).is_none());
+        assert!(graph.lca_many(&["Gang", "Tisch"]).is_none());
+    }
+
+    #[test]
+    fn modify_changes_data_of_existing_node() {
+        let mut graph = sample_graph();
+        assert!(graph.modify(&"Essen", |data| *data = "Pizza?"));
+        assert_eq!(graph.get_node(&"Essen").unwrap().data, "Pizza?");
+        assert!(!graph.modify(&"Fenster", |data| *data = "Fenster?"));
+    }
+
+    #[test]
+    fn paths_as_strings_joins_keys() {
+        assert_eq!(
+            sample_graph().paths_as_strings("/"),
+            ["Start/Essen", "Start/Sitzplatz/Gang"]
+        );
+    }
+
+    #[test]
+    fn can_append_checks_without_mutating() {
+        let mut graph = Graph::new();
+        assert_eq!(
+            graph.can_append(&Node::new("Hallo", "Start", "Vorher")),
+            Err(AppendError::RootWithFather("Start"))
+        );
+        assert_eq!(graph.can_append(&Node::new("Hallo", "Start", None)), Ok(()));
+        assert!(graph.is_empty());
+
+        graph
+            .try_append_node(Node::new("Hallo", "Start", None))
+            .unwrap();
+        assert_eq!(
+            graph.can_append(&Node::new("Essen?", "Essen", None)),
+            Err(AppendError::MissingFather("Essen"))
+        );
+        assert_eq!(
+            graph.can_append(&Node::new("Essen?", "Essen", "Fenster")),
+            Err(AppendError::FatherNotFound("Fenster"))
+        );
+        assert_eq!(
+            graph.can_append(&Node::new("Essen?", "Essen", "Start")),
+            Ok(())
+        );
+        assert_eq!(graph.len(), 1);
+        assert_eq!(
+            graph.try_append_node(Node::new("Essen?", "Essen", "Fenster")),
+            Err(AppendError::FatherNotFound("Fenster"))
+        );
+        assert_eq!(graph.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "No father with key")]
+    fn append_node_panics_without_father() {
+        let mut graph = sample_graph();
+        graph.append_node(Node::new("Am Fenster.", "Fenster", "Tisch"));
+    }
+
+    #[test]
+    fn iter_edges_yields_father_child_pairs() {
+        let graph = sample_graph();
+        let (father, child) = graph
+            .iter_edges()
+            .find(|(_, child)| child.data.starts_with("Ok, dann"))
+            .unwrap();
+        assert_eq!((father.key, child.key), ("Sitzplatz", "Gang"));
+        assert_eq!(graph.iter_edges().count(), 3);
+    }
+
+    #[test]
+    fn is_isomorphic_to_ignores_keys_and_child_order() {
+        let graph = sample_graph();
+        let mut other = Graph::new();
+        other.append_node(Node::new("", "a", None));
+        other.append_node(Node::new("", "b", "a"));
+        other.append_node(Node::new("", "c", "b"));
+        other.append_node(Node::new("", "d", "a"));
+        assert!(graph.is_isomorphic_to(&other));
+
+        other.append_node(Node::new("", "e", "d"));
+        assert!(!graph.is_isomorphic_to(&other));
+        assert!(Graph::<&str, &str>::new().is_isomorphic_to(&Graph::new()));
+    }
+
+    #[test]
+    fn iter_inorder_visits_left_node_right() {
+        let mut graph = Graph::new();
+        graph.append_node(Node::new("", 4, None));
+        graph.append_node(Node::new("", 2, 4));
+        graph.append_node(Node::new("", 6, 4));
+        graph.append_node(Node::new("", 1, 2));
+        graph.append_node(Node::new("", 3, 2));
+        graph.append_node(Node::new("", 5, 6));
+        graph.append_node(Node::new("", 7, 6));
+        let order: Vec<_> = graph.iter_inorder().map(|node| node.key).collect();
+        assert_eq!(order, [1, 2, 3, 4, 5, 6, 7]);
+
+        let order: Vec<_> = sample_graph().iter_inorder().map(|node| node.key).collect();
+        assert_eq!(order, ["Essen", "Start", "Sitzplatz", "Gang"]);
+    }
+
+    #[test]
+    fn find_default_data_returns_placeholders() {
+        let mut graph = sample_graph();
+        assert!(graph.find_default_data().is_empty());
+        graph.append_node(Node::new("", "Fenster", "Sitzplatz"));
+        assert_eq!(graph.find_default_data(), [&"Fenster"]);
+    }
+
+    #[test]
+    fn checksum_is_reproducible() {
+        let graph = sample_graph();
+        assert_eq!(graph.checksum(), sample_graph().checksum());
+
+        let mut changed = sample_graph();
+        changed.modify(&"Gang", |data| *data = "Bis dann!");
+        assert_ne!(graph.checksum(), changed.checksum());
+
+        let mut tagged = sample_graph();
+        tagged.add_tag(&"Gang", "ending".to_string());
+        assert_ne!(graph.checksum(), tagged.checksum());
+    }
+
+    #[test]
+    fn unexpected_leaves_flags_improper_endings() {
+        let mut graph = sample_graph();
+        graph.append_node(Node::new("Am Fenster", "Fenster", "Sitzplatz"));
+        let unexpected = graph.unexpected_leaves(|data| data.ends_with('.') || data.ends_with('!'));
+        assert_eq!(keys(&unexpected), ["Essen", "Fenster"]);
+        assert_eq!(
+            keys(&graph.unexpected_leaves(|data| data.ends_with('?'))),
+            ["Gang", "Fenster"]
+        );
+    }
+
+    #[test]
+    fn reparent_many_applies_all_moves_or_none() {
+        let mut graph = sample_graph();
+        assert_eq!(
+            graph.reparent_many(&[("Essen", "Sitzplatz"), ("Sitzplatz", "Gang")]),
+            Err(vec![ReparentError::Cycle {
+                key: "Sitzplatz",
+                new_father: "Gang"
+            }])
+        );
+        assert!(graph.structurally_eq(&sample_graph()));
+
+        assert_eq!(
+            graph.reparent_many(&[("Start", "Gang"), ("Fenster", "Start")]),
+            Err(vec![
+                ReparentError::RootMove("Start"),
+                ReparentError::KeyNotFound("Fenster")
+            ])
+        );
+
+        graph
+            .reparent_many(&[("Essen", "Sitzplatz"), ("Gang", "Essen")])
+            .unwrap();
+        assert!(graph
+            .travel_to_node(&["Sitzplatz", "Essen", "Gang"])
+            .is_some());
+        assert_eq!(graph.nodes[0].children, ["Sitzplatz"]);
+        assert!(graph.is_tree());
+    }
+
+    #[test]
+    fn subtree_data_collects_in_pre_order() {
+        let graph = sample_graph();
+        assert_eq!(
+            graph.subtree_data(&"Start").unwrap(),
+            [
+                &"Hallo, willst du etwas Essen gehen, oder einen Sitzplatz buchen?",
+                &"Ok, was willst du essen? Pizza oder Pasta?",
+                &"Ok, willst du am Fenster oder am Gang sitzen?",
+                &"Ok, dann einen Sitzlatz am Gang. Bis dann!"
+            ]
+        );
+        assert!(graph.subtree_data(&"Fenster").is_none());
+    }
+
+    #[test]
+    fn duplicate_subtree_copies_branch() {
+        let mut graph = sample_graph().map_keys(|key| key.to_string());
+        let copy = graph
+            .duplicate_subtree(&"Sitzplatz".to_string(), &"Essen".to_string(), |key| {
+                format!("{}2", key)
+            })
+            .unwrap();
+        assert_eq!(copy, "Sitzplatz2");
+        assert_eq!(graph.len(), 6);
+        let route = ["Essen", "Sitzplatz2", "Gang2"].map(String::from);
+        assert_eq!(
+            graph.travel_to_node(&route).unwrap().data,
+            "Ok, dann einen Sitzlatz am Gang. Bis dann!"
+        );
+        assert!(graph.is_tree());
+
+        assert_eq!(
+            graph.duplicate_subtree(&"Sitzplatz".to_string(), &"Start".to_string(), |key| {
+                key.clone()
+            }),
+            Err(DuplicateError::KeyCollision("Sitzplatz".to_string()))
+        );
+        assert_eq!(graph.len(), 6);
+    }
+
+    #[test]
+    fn subtrees_where_matches_whole_subtrees() {
+        let graph = sample_graph();
+        assert_eq!(
+            graph.subtrees_where(|nodes| nodes.len() == 1),
+            [&"Essen", &"Gang"]
+        );
+        assert_eq!(
+            graph.subtrees_where(|nodes| nodes.iter().all(|node| node.data.contains("Gang"))),
+            [&"Sitzplatz", &"Gang"]
+        );
+        assert_eq!(graph.subtrees_where(|nodes| nodes.len() == 4), [&"Start"]);
+    }
+
+    #[test]
+    fn components_splits_forest_into_trees() {
+        let mut graph = sample_graph();
+        graph.nodes.push(Node::new("Tschüss", "Ende", None));
+        graph.nodes.push(Node::new("Bis bald", "Bald", "Ende"));
+        graph.nodes[4].children.push("Bald");
+
+        let components = graph.components();
+        assert_eq!(components.len(), 2);
+        assert!(components[0].structurally_eq(&sample_graph()));
+        assert_eq!(components[1].len(), 2);
+        assert!(components.iter().all(|component| component.is_tree()));
+    }
+
+    #[test]
+    fn is_valid_route_checks_route() {
+        let graph = sample_graph();
+        assert!(graph.is_valid_route(&["Sitzplatz", "Gang"]));
+        assert!(!graph.is_valid_route(&["Essen", "Gang"]));
+    }
+
+    #[test]
+    fn trim_leaves_once_does_not_cascade() {
+        let mut graph = sample_graph();
+        assert_eq!(graph.trim_leaves_once(|_| true), 2);
+        assert_eq!(keys(&graph.leaves()), ["Sitzplatz"]);
+        assert_eq!(graph.len(), 2);
+        assert_eq!(graph.trim_leaves_once(|node| node.key == "Start"), 0);
+    }
+
+    #[test]
+    fn shallowest_returns_node_nearest_to_root() {
+        let graph = sample_graph();
+        assert_eq!(graph.shallowest(&["Gang", "Start"]).unwrap().key, "Start");
+        assert_eq!(
+            graph.shallowest(&["Sitzplatz", "Essen"]).unwrap().key,
+            "Essen"
+        );
+        assert!(graph.shallowest(&["Fenster"]).is_none());
+    }
+
+    #[test]
+    fn deepest_returns_end_of_longest_branch() {
+        let mut graph = sample_graph();
+        assert_eq!(graph.deepest().unwrap().key, "Gang");
+        graph.append_node(Node::new("Am Fenster.", "Fenster", "Sitzplatz"));
+        assert_eq!(graph.deepest().unwrap().key, "Gang");
+        assert!(Graph::<&str, &str>::new().deepest().is_none());
+    }
+
+    #[test]
+    fn path_length_histogram_counts_leaf_depths() {
+        let histogram = sample_graph().path_length_histogram();
+        assert_eq!(histogram.into_iter().collect::<Vec<_>>(), [(1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn reversed_turns_leaves_into_roots() {
+        let graph = sample_graph().reversed();
+        assert!(graph.get_node(&"Start").unwrap().children.is_empty());
+        assert_eq!(keys(&graph.leaves()), ["Start"]);
+        let roots: Vec<_> = graph
+            .nodes
+            .iter()
+            .filter(|node| node.father_key.is_none())
+            .map(|node| node.key)
+            .collect();
+        assert_eq!(roots, ["Essen", "Gang"]);
+        assert_eq!(graph.path_to(&"Start"), Some(vec!["Essen", "Start"]));
+        assert_eq!(
+            graph.get_node(&"Gang").unwrap().data,
+            "Ok, dann einen Sitzlatz am Gang. Bis dann!"
+        );
+    }
+
+    #[test]
+    fn all_exist_requires_every_key() {
+        let graph = sample_graph();
+        assert!(graph.all_exist(&["Gang", "Start", "Essen"]));
+        assert!(graph.all_exist(&[]));
+        assert!(!graph.all_exist(&["Gang", "Fenster"]));
+    }
+
+    #[test]
+    fn travel_to_node_mut_edits_reached_node() {
+        let mut graph = sample_graph();
+        graph
+            .travel_to_node_mut(&["Sitzplatz", "Gang"])
+            .unwrap()
+            .data = "Bis dann!";
+        assert_eq!(graph.get_node(&"Gang").unwrap().data, "Bis dann!");
+        assert!(graph.travel_to_node_mut(&["Essen", "Gang"]).is_none());
+    }
+
+    #[test]
+    fn to_sexpr_wraps_nodes_in_parens() {
+        assert_eq!(
+            sample_graph().to_sexpr(),
+            "(Start (Essen) (Sitzplatz (Gang)))"
+        );
+        assert_eq!(Graph::<&str, &str>::new().to_sexpr(), "");
+
+        let mut graph = Graph::new();
+        graph.append_node(Node::new((), "a b", None));
+        graph.append_node(Node::new((), "(\"x\")", "a b"));
+        graph.append_node(Node::new((), "", "a b"));
+        assert_eq!(graph.to_sexpr(), "(\"a b\" (\"(\\\"x\\\")\") (\"\"))");
+    }
+
+    #[test]
+    fn from_sexpr_round_trips_to_sexpr() {
+        let sexpr = sample_graph().to_sexpr();
+        let graph: Graph<usize, String> =
+            Graph::from_sexpr(&sexpr, |key: &String| key.len()).unwrap();
+        assert_eq!(graph.to_sexpr(), sexpr);
+        assert_eq!(graph.get_node(&"Sitzplatz".to_string()).unwrap().data, 9);
+
+        let graph: Graph<(), u32> = Graph::from_sexpr("\n ( 1\t(2 (3))\n(4) ) ", |_| ()).unwrap();
+        assert_eq!(graph.to_sexpr(), "(1 (2 (3)) (4))");
+        assert!(Graph::<(), u32>::from_sexpr("  ", |_| ())
+            .unwrap()
+            .is_empty());
+
+        let mut graph = Graph::new();
+        graph.append_node(Node::new((), "a b".to_string(), None));
+        graph.append_node(Node::new((), "(\"x\\y\")".to_string(), "a b".to_string()));
+        graph.append_node(Node::new((), String::new(), "a b".to_string()));
+        let sexpr = graph.to_sexpr();
+        let parsed: Graph<(), String> = Graph::from_sexpr(&sexpr, |_| ()).unwrap();
+        assert_eq!(parsed.to_sexpr(), sexpr);
+        assert!(parsed.contains_key(&"(\"x\\y\")".to_string()));
+        assert!(parsed.contains_key(&String::new()));
+    }
+
+    #[test]
+    fn from_sexpr_rejects_invalid_input() {
+        let parse = |s| Graph::<(), u32>::from_sexpr(s, |_| ()).err();
+        assert_eq!(parse("(1 (2)"), Some(ParseError::UnexpectedEnd));
+        assert_eq!(parse("(1) (2)"), Some(ParseError::UnexpectedCharacter(4)));
+        assert_eq!(parse("(1 2)"), Some(ParseError::UnexpectedCharacter(3)));
+        assert_eq!(parse("(a)"), Some(ParseError::InvalidKey("a".to_string())));
+        assert_eq!(parse("(\"1)"), Some(ParseError::UnexpectedEnd));
+        assert_eq!(
+            parse("(\"1\\n\")"),
+            Some(ParseError::UnexpectedCharacter(4))
+        );
+        assert_eq!(
+            parse("(1 (1))"),
+            Some(ParseError::DuplicateKey("1".to_string()))
+        );
+    }
+
+    #[test]
+    fn scan_paths_accumulates_down_each_path() {
+        let mut graph = Graph::new();
+        graph.append_node(Node::new("Hallo", "Start", None));
+        graph.append_node(Node::new("Essen", "Essen", "Start"));
+        graph.append_node(Node::new("Sitzplatz", "Sitzplatz", "Start"));
+        graph.append_node(Node::new("Gang", "Gang", "Sitzplatz"));
+        assert_eq!(
+            graph.scan_paths(0, |length, data| length + data.len()),
+            [("Start", 5), ("Essen", 10), ("Sitzplatz", 14), ("Gang", 18)]
+        );
+    }
+
+    #[test]
+    fn first_duplicate_key_finds_repeated_key() {
+        let mut graph = sample_graph();
+        assert_eq!(graph.first_duplicate_key(), None);
+        graph.nodes.push(Node::new("Am Gang.", "Gang", "Start"));
+        graph.nodes.push(Node::new("Essen!", "Essen", "Start"));
+        assert_eq!(graph.first_duplicate_key(), Some(&"Gang"));
+    }
+
+    #[test]
+    fn set_max_children_limits_appends() {
+        let mut graph = sample_graph();
+        graph.set_max_children(2);
+        assert_eq!(
+            graph.try_append_node(Node::new("Trinken?", "Trinken", "Start")),
+            Err(AppendError::TooManyChildren("Start"))
+        );
+        assert_eq!(
+            graph.insert_child_at(&"Start", Node::new("Trinken?", "Trinken", None), 0),
+            Err(AppendError::TooManyChildren("Start"))
+        );
+        assert_eq!(graph.len(), 4);
+        graph
+            .try_append_node(Node::new("Am Fenster.", "Fenster", "Sitzplatz"))
+            .unwrap();
+        assert_eq!(
+            graph.can_append(&Node::new("Tisch.", "Tisch", "Sitzplatz")),
+            Err(AppendError::TooManyChildren("Sitzplatz"))
+        );
+    }
+
+    #[test]
+    fn within_hops_returns_neighbourhood() {
+        let graph = sample_graph();
+        assert_eq!(
+            keys(&graph.within_hops(&"Sitzplatz", 1)),
+            ["Start", "Sitzplatz", "Gang"]
+        );
+        assert_eq!(keys(&graph.within_hops(&"Gang", 0)), ["Gang"]);
+        assert_eq!(graph.within_hops(&"Gang", 3).len(), 4);
+        assert!(graph.within_hops(&"Fenster", 1).is_empty());
+    }
+
+    #[test]
+    fn centroid_of_chain_is_middle_node() {
+        let mut graph = Graph::new();
+        graph.append_node(Node::new("a", 1, None));
+        graph.append_node(Node::new("b", 2, 1));
+        graph.append_node(Node::new("c", 3, 2));
+        graph.append_node(Node::new("d", 4, 3));
+        graph.append_node(Node::new("e", 5, 4));
+        assert_eq!(graph.centroid().map(|node| node.key), Some(3));
+        assert_eq!(
+            sample_graph().centroid().map(|node| node.key),
+            Some("Start")
+        );
+        assert!(Graph::<(), u8>::new().centroid().is_none());
+
+        let mut looped = Graph::new();
+        looped.append_node(Node::new(0, "A", None));
+        looped.append_node(Node::new(1, "A", "A"));
+        assert_eq!(looped.centroid().map(|node| node.key), Some("A"));
+        let mut shared = Graph::new();
+        shared.append_node(Node::new(0, "S", None));
+        shared.append_node(Node::new(1, "X", "S"));
+        shared.append_node(Node::new(2, "Y", "S"));
+        shared.append_node(Node::new(3, "Z", "X"));
+        shared.nodes[2].children.push("Z");
+        assert_eq!(shared.centroid().map(|node| node.key), Some("S"));
+    }
+
+    #[test]
+    fn write_edges_writes_tab_separated_lines() {
+        let mut buffer = Vec::new();
+        sample_graph().write_edges(&mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "Start\nStart\tEssen\nStart\tSitzplatz\nSitzplatz\tGang\n"
+        );
+
+        let mut graph = Graph::new();
+        graph.append_node(Node::new((), "", None));
+        graph.append_node(Node::new((), "a\tb\\", ""));
+        graph.append_node(Node::new((), "c\nd\r", ""));
+        let mut buffer = Vec::new();
+        graph.write_edges(&mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "\\0\n\\0\ta\\tb\\\\\n\\0\tc\\nd\\r\n"
+        );
+    }
+
+    #[test]
+    fn read_edges_round_trips_write_edges() {
+        let mut buffer = Vec::new();
+        sample_graph().write_edges(&mut buffer).unwrap();
+        let graph: Graph<usize, String> =
+            Graph::read_edges(buffer.as_slice(), |key: &String| key.len()).unwrap();
+        let mut written = Vec::new();
+        graph.write_edges(&mut written).unwrap();
+        assert_eq!(written, buffer);
+        assert_eq!(
+            graph.get_node(&"Gang".to_string()).map(|node| node.data),
+            Some(4)
+        );
+        let mut single = Graph::new();
+        single.append_node(Node::new((), 7u8, None));
+        let mut buffer = Vec::new();
+        single.write_edges(&mut buffer).unwrap();
+        let graph = Graph::<(), u8>::read_edges(buffer.as_slice(), |_| ()).unwrap();
+        assert_eq!(graph.len(), 1);
+        assert!(graph.contains_key(&7));
+        let mut escaped: Graph<(), String> = Graph::new();
+        escaped.append_node(Node::new((), "a\tb".to_string(), None));
+        escaped.append_node(Node::new((), "c\nd".to_string(), "a\tb".to_string()));
+        escaped.append_node(Node::new((), String::new(), "a\tb".to_string()));
+        let mut buffer = Vec::new();
+        escaped.write_edges(&mut buffer).unwrap();
+        let graph = Graph::<(), String>::read_edges(buffer.as_slice(), |_| ()).unwrap();
+        assert_eq!(graph.len(), 3);
+        assert!(graph.structurally_eq(&escaped));
+        let mut empty_root: Graph<(), String> = Graph::new();
+        empty_root.append_node(Node::new((), String::new(), None));
+        let mut buffer = Vec::new();
+        empty_root.write_edges(&mut buffer).unwrap();
+        let graph = Graph::<(), String>::read_edges(buffer.as_slice(), |_| ()).unwrap();
+        assert_eq!(graph.len(), 1);
+        assert!(graph.contains_key(&String::new()));
+        let read = |s: &str| Graph::<(), u8>::read_edges(s.as_bytes(), |_| ());
+        assert!(read("").is_ok_and(|graph| graph.is_empty()));
+        assert_eq!(
+            read("1\t2\n3\t4\t5").err(),
+            Some(ImportError::InvalidLine(2))
+        );
+        assert_eq!(read("1\n1\t2").ok().map(|graph| graph.len()), Some(2));
+        assert_eq!(read("1\n3").err(), Some(ImportError::InvalidRoot));
+        assert_eq!(read("1\t\\x").err(), Some(ImportError::InvalidLine(1)));
+        assert_eq!(
+            read("1\t2\n3\t2").err(),
+            Some(ImportError::DuplicateKey("2".to_string()))
+        );
+        assert_eq!(read("1\t2\n3\t4").err(), Some(ImportError::InvalidRoot));
+        assert_eq!(
+            read("1\tx").err(),
+            Some(ImportError::InvalidKey("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn impact_of_removal_counts_subtree() {
+        let graph = sample_graph();
+        assert_eq!(graph.impact_of_removal(&"Sitzplatz"), Some(2));
+        assert_eq!(graph.impact_of_removal(&"Start"), Some(4));
+        assert_eq!(graph.impact_of_removal(&"Fenster"), None);
+    }
+
+    #[test]
+    fn deletion_order_lists_children_first() {
+        let graph = sample_graph();
+        assert_eq!(
+            graph.deletion_order(&"Sitzplatz"),
+            Some(vec!["Gang", "Sitzplatz"])
+        );
+        assert_eq!(
+            graph.deletion_order(&"Start"),
+            Some(vec!["Essen", "Gang", "Sitzplatz", "Start"])
+        );
+        assert_eq!(graph.deletion_order(&"Fenster"), None);
+    }
+
+    #[test]
+    fn are_siblings_checks_shared_father() {
+        let graph = sample_graph();
+        assert!(graph.are_siblings(&"Essen", &"Sitzplatz"));
+        assert!(!graph.are_siblings(&"Essen", &"Gang"));
+        assert!(!graph.are_siblings(&"Essen", &"Essen"));
+        assert!(!graph.are_siblings(&"Essen", &"Fenster"));
+    }
+
+    #[test]
+    fn to_data_map_maps_keys_to_data() {
+        let graph = sample_graph();
+        let map = graph.to_data_map();
+        assert_eq!(map.len(), 4);
+        assert_eq!(
+            map.get(&"Gang"),
+            Some(&&"Ok, dann einen Sitzlatz am Gang. Bis dann!")
+        );
+        assert_eq!(map.get(&"Fenster"), None);
+    }
+
+    #[test]
+    fn max_by_data_finds_longest_data() {
+        let graph = sample_graph();
+        let longest = graph.max_by_data(|a, b| a.len().cmp(&b.len()));
+        assert_eq!(longest.map(|node| node.key), Some("Start"));
+        let tied = graph.max_by_data(|_, _| std::cmp::Ordering::Equal);
+        assert_eq!(tied.map(|node| node.key), Some("Start"));
+        assert!(Graph::<&str, &str>::new().max_by_data(Ord::cmp).is_none());
+    }
+
+    #[test]
+    fn leaves_uniform_depth_requires_equal_leaf_depths() {
+        let mut graph = sample_graph();
+        assert_eq!(graph.leaves_uniform_depth(), None);
+        graph.append_node(Node::new("Pizza", "Pizza", "Essen"));
+        assert_eq!(graph.leaves_uniform_depth(), Some(2));
+        assert_eq!(Graph::<&str, &str>::new().leaves_uniform_depth(), None);
+    }
+
+    #[test]
+    fn to_tree_string_unicode_draws_connectors() {
+        let mut graph = sample_graph();
+        graph.append_node(Node::new("Pizza", "Pizza", "Essen"));
+        let tree = graph.to_tree_string_unicode();
+        assert!(tree.contains("└── Gang"));
+        assert_eq!(
+            tree,
+            "Start\n├── Essen\n│   └── Pizza\n└── Sitzplatz\n    └── Gang\n"
+        );
+        assert_eq!(Graph::<&str, &str>::new().to_tree_string_unicode(), "");
+    }
+
+    #[test]
+    fn mark_terminal_rejects_children() {
+        let mut graph = sample_graph();
+        graph.mark_terminal(&"Gang");
+        assert_eq!(
+            graph.try_append_node(Node::new("Fenster", "Fenster", "Gang")),
+            Err(AppendError::TerminalFather("Gang"))
+        );
+        assert_eq!(
+            graph.insert_child_at(&"Gang", Node::new("Fenster", "Fenster", None), 0),
+            Err(AppendError::TerminalFather("Gang"))
+        );
+        assert!(graph
+            .try_append_node(Node::new("Fenster", "Fenster", "Sitzplatz"))
+            .is_ok());
+    }
+
+    #[test]
+    fn diameter_counts_longest_path() {
+        assert_eq!(sample_graph().diameter(), 3);
+        let mut graph = Graph::new();
+        graph.append_node(Node::new("a", 1, None));
+        assert_eq!(graph.diameter(), 0);
+        graph.append_node(Node::new("b", 2, 1));
+        graph.append_node(Node::new("c", 3, 2));
+        assert_eq!(graph.diameter(), 2);
+    }
+
+    #[test]
+    fn diameter_endpoints_returns_ends_of_longest_path() {
+        let graph = sample_graph();
+        let (a, b) = graph.diameter_endpoints().unwrap();
+        assert_eq!((a.key, b.key), ("Essen", "Gang"));
+        let mut chain = Graph::new();
+        assert!(chain.diameter_endpoints().is_none());
+        chain.append_node(Node::new("a", 1, None));
+        chain.append_node(Node::new("b", 2, 1));
+        chain.append_node(Node::new("c", 3, 2));
+        let (a, b) = chain.diameter_endpoints().unwrap();
+        assert_eq!((a.key, b.key), (3, 1));
+    }
+
+    #[test]
+    fn set_root_migrates_children() {
+        let mut graph = sample_graph();
+        let old_root = graph
+            .set_root(Node::new("Willkommen", "Anfang", None))
+            .unwrap();
+        assert_eq!(old_root.key, "Start");
+        assert_eq!(old_root.child_count(), 0);
+        assert_eq!(graph.len(), 4);
+        assert!(!graph.contains_key(&"Start"));
+        assert_eq!(
+            graph.path_between(&"Anfang", &"Gang"),
+            Some(vec!["Anfang", "Sitzplatz", "Gang"])
+        );
+        assert_eq!(
+            graph.set_root(Node::new("", "Gang", None)).err(),
+            Some(SetRootError::KeyCollision("Gang"))
+        );
+        assert_eq!(
+            graph.set_root(Node::new("", "Neu", "Gang")).err(),
+            Some(SetRootError::RootWithFather("Neu"))
+        );
+        assert_eq!(
+            Graph::new().set_root(Node::new("", "Neu", None)).err(),
+            Some(SetRootError::EmptyGraph)
+        );
+    }
+
+    #[test]
+    fn distinct_data_count_ignores_repeated_data() {
+        let mut graph = sample_graph();
+        assert_eq!(graph.distinct_data_count(), 4);
+        graph.append_node(Node::new(
+            "Ok, dann einen Sitzlatz am Gang. Bis dann!",
+            "Fenster",
+            "Sitzplatz",
+        ));
+        assert_eq!(graph.distinct_data_count(), 4);
+        assert_eq!(Graph::<&str, &str>::new().distinct_data_count(), 0);
+    }
+
+    #[test]
+    fn duplicate_data_groups_groups_equal_data() {
+        let mut graph = sample_graph();
+        assert!(graph.duplicate_data_groups().is_empty());
+        graph.append_node(Node::new(
+            "Ok, dann einen Sitzlatz am Gang. Bis dann!",
+            "Fenster",
+            "Sitzplatz",
+        ));
+        assert_eq!(
+            graph.duplicate_data_groups(),
+            vec![vec![&"Gang", &"Fenster"]]
+        );
+    }
+
+    #[test]
+    fn topological_order_puts_fathers_first() {
+        let graph = sample_graph();
+        let order = graph.topological_order();
+        assert_eq!(order.len(), graph.len());
+        for (father, child) in graph.iter_edges() {
+            let position = |key: &&str| order.iter().position(|other| *other == key);
+            assert!(position(&father.key) < position(&child.key));
+        }
+    }
+
+    #[test]
+    fn merge_at_root_unions_subtrees() {
+        let mut graph = sample_graph();
+        let mut other = Graph::new();
+        other.append_node(Node::new("Hallo", "Start", None));
+        other.append_node(Node::new("Sitzplatz", "Sitzplatz", "Start"));
+        other.append_node(Node::new("Fenster", "Fenster", "Sitzplatz"));
+        other.append_node(Node::new("Trinken", "Trinken", "Start"));
+        graph.merge_at_root(other).unwrap();
+        assert_eq!(graph.len(), 6);
+        assert_eq!(
+            graph.get_node(&"Start").map(|node| node.data),
+            Some("Hallo, willst du etwas Essen gehen, oder einen Sitzplatz buchen?")
+        );
+        assert_eq!(
+            graph
+                .children_iter(&"Sitzplatz")
+                .map(|node| node.key)
+                .collect::<Vec<_>>(),
+            ["Gang", "Fenster"]
+        );
+        assert!(graph.is_tree());
+
+        let mut other = Graph::new();
+        other.append_node(Node::new("", "Start", None));
+        other.append_node(Node::new("", "Gang", "Start"));
+        assert_eq!(
+            graph.merge_at_root(other).err(),
+            Some(MergeError::KeyConflict("Gang"))
+        );
+        let mut other = Graph::new();
+        other.append_node(Node::new("", "Anfang", None));
+        assert_eq!(
+            graph.merge_at_root(other).err(),
+            Some(MergeError::RootMismatch("Anfang"))
+        );
+    }
+
+    #[test]
+    fn branching_ratio_counts_branching_fathers() {
+        assert_eq!(sample_graph().branching_ratio(), 0.5);
+        let mut graph = Graph::new();
+        graph.append_node(Node::new((), 1, None));
+        assert_eq!(graph.branching_ratio(), 0.0);
+    }
+
+    #[test]
+    fn restore_rolls_back_to_snapshot() {
+        let mut graph = sample_graph();
+        let original = graph.snapshot();
+        let checksum = graph.checksum();
+        graph.set_max_children(1);
+        graph.remove_node_with_childs("Sitzplatz");
+        graph.add_tag(&"Essen", "hungrig".to_string());
+        assert_ne!(graph.checksum(), checksum);
+        graph.restore(original);
+        assert_eq!(graph.checksum(), checksum);
+        assert!(graph.structurally_eq(&sample_graph()));
+        assert!(graph
+            .try_append_node(Node::new("Trinken", "Trinken", "Start"))
+            .is_ok());
+    }
+
+    #[test]
+    fn nearest_ancestor_walks_up_from_father() {
+        let graph = sample_graph();
+        let greeting = graph.nearest_ancestor(&"Gang", |node| node.data.starts_with("Hallo"));
+        assert_eq!(greeting.map(|node| node.key), Some("Start"));
+        assert!(graph.nearest_ancestor(&"Start", |_| true).is_none());
+        assert_eq!(
+            graph
+                .nearest_ancestor(&"Gang", |_| true)
+                .map(|node| node.key),
+            Some("Sitzplatz")
+        );
+    }
+
+    #[test]
+    fn subtrees_yield_every_subtree() {
+        let graph = sample_graph();
+        let subtrees: Vec<_> = graph.subtrees().collect();
+        assert_eq!(subtrees.len(), 4);
+        let (_, sitzplatz) = subtrees
+            .iter()
+            .find(|(node, _)| node.key == "Sitzplatz")
+            .unwrap();
+        assert_eq!(keys(sitzplatz), ["Sitzplatz", "Gang"]);
+        assert_eq!(
+            keys(&subtrees[0].1),
+            ["Start", "Essen", "Sitzplatz", "Gang"]
+        );
+
+        let mut looped = Graph::new();
+        looped.append_node(Node::new(0, "A", None));
+        looped.append_node(Node::new(1, "A", "A"));
+        assert_eq!(looped.subtrees().count(), 1);
+        let mut shared = Graph::new();
+        shared.append_node(Node::new(0, "S", None));
+        shared.append_node(Node::new(1, "X", "S"));
+        shared.append_node(Node::new(2, "Y", "S"));
+        shared.append_node(Node::new(3, "Z", "X"));
+        shared.nodes[2].children.push("Z");
+        let sizes: Vec<_> = shared
+            .subtrees()
+            .map(|(node, subtree)| (node.key, subtree.len()))
+            .collect();
+        assert_eq!(sizes, [("S", 4), ("X", 2), ("Z", 1), ("Y", 1)]);
+    }
+
+    #[test]
+    fn has_ambiguous_children_finds_repeated_child_keys() {
+        let mut graph = sample_graph();
+        assert!(!graph.has_ambiguous_children());
+        let mut node = Node::new("Pizza", "Pizza", "Essen");
+        node.children = vec!["Salami", "Funghi", "Salami"];
+        graph.append_node(node);
+        assert!(graph.has_ambiguous_children());
+    }
+
+    #[test]
+    fn to_mermaid_writes_flowchart() {
+        let mermaid = sample_graph().to_mermaid();
+        assert!(mermaid.starts_with("graph TD\n"));
+        assert!(mermaid.contains("    Sitzplatz --> Gang\n"));
+        assert!(mermaid.contains("    Start --> Essen\n"));
+        let mut graph = Graph::new();
+        graph.append_node(Node::new((), "Start", None));
+        graph.append_node(Node::new((), "Sag \"Hallo\"", "Start"));
+        assert_eq!(
+            graph.to_mermaid(),
+            "graph TD\n    Start\n    node1[\"Sag #quot;Hallo#quot;\"]\n    Start --> node1\n"
+        );
+        graph.append_node(Node::new((), "node1", "Start"));
+        assert_eq!(
+            graph.to_mermaid(),
+            "graph TD\n    Start\n    node1_[\"Sag #quot;Hallo#quot;\"]\n    node1\n    Start --> node1_\n    Start --> node1\n"
+        );
+        let mut graph = Graph::new();
+        graph.append_node(Node::new((), "graph", None));
+        graph.append_node(Node::new((), "Style", "graph"));
+        assert_eq!(
+            graph.to_mermaid(),
+            "graph TD\n    node0[\"graph\"]\n    Style\n    node0 --> Style\n"
+        );
+        assert_eq!(Graph::<(), &str>::new().to_mermaid(), "graph TD\n");
+    }
+
+    #[test]
+    fn traversals_end_on_node_listed_as_own_child() {
+        let mut graph = Graph::new();
+        graph.append_node(Node::new(0, "A", None));
+        graph.append_node(Node::new(1, "A", "A"));
+        assert_eq!(graph.iter_order(TraversalOrder::PreOrder).count(), 1);
+        assert_eq!(graph.iter_order(TraversalOrder::PostOrder).count(), 1);
+        assert_eq!(graph.iter_order(TraversalOrder::LevelOrder).count(), 1);
+        assert!(!graph.stats().is_tree);
+        assert_eq!(graph.total_depth(), 0);
+        assert_eq!(
+            graph.to_nested_json(),
+            "{\"key\":\"A\",\"data\":\"0\",\"children\":[]}"
+        );
+        assert_eq!(graph.to_sexpr(), "(A)");
+        assert_eq!(graph.to_tree_string_unicode(), "A\n");
+        assert_eq!(graph.iter_inorder().count(), 1);
+        assert!(graph.diameter_endpoints().is_some());
+        assert_eq!(graph.scan_paths(0, |sum, data| sum + data), vec![("A", 0)]);
+    }
+
+    #[test]
+    fn subtree_sizes_count_every_node_once() {
+        let mut looped = Graph::new();
+        looped.append_node(Node::new(0, "A", None));
+        looped.append_node(Node::new(1, "A", "A"));
+        let mut shared = Graph::new();
+        shared.append_node(Node::new(0, "S", None));
+        shared.append_node(Node::new(1, "X", "S"));
+        shared.append_node(Node::new(2, "Y", "S"));
+        shared.append_node(Node::new(3, "Z", "X"));
+        shared.nodes[2].children.push("Z");
+        assert_eq!(looped.subtrees_where(|_| true), vec![&"A"]);
+        assert_eq!(
+            shared.subtrees_where(|_| true),
+            vec![&"S", &"X", &"Z", &"Y"]
+        );
     }
 }